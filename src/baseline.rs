@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// Rule ID that link failures are recorded under.
+pub const LINK_RULE_ID: &str = "LINK";
+
+/// Format version written to baseline files.
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum BaselineError {
+    #[error("failed to access baseline {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("invalid baseline file {path}: {source}")]
+    Format {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("unsupported baseline version {version} in {path}; write it again")]
+    Version { path: PathBuf, version: u32 },
+}
+
+/// Identifies a diagnostic independently of its line number, so that edits
+/// elsewhere in a file do not invalidate the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub rule_id: String,
+    /// Path relative to the current directory, with `/` separators.
+    pub file: String,
+    /// The line the diagnostic is on, trimmed and with whitespace collapsed.
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Fingerprint {
+    /// Fingerprints a diagnostic of `rule_id` on the line `line` of `file`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::baseline::Fingerprint;
+    /// use std::path::Path;
+    ///
+    /// let a = Fingerprint::new("NO_HTML", Path::new("./docs/a.md"), "  <div>x</div> ", None);
+    /// let b = Fingerprint::new("NO_HTML", Path::new("docs/a.md"), "<div>x</div>", None);
+    ///
+    /// assert_eq!(a, b);
+    /// assert_eq!(a.file, "docs/a.md");
+    /// ```
+    pub fn new(rule_id: &str, file: &Path, line: &str, url: Option<&str>) -> Self {
+        Self {
+            rule_id: rule_id.to_string(),
+            file: normalize_path(file),
+            content: line.split_whitespace().collect::<Vec<_>>().join(" "),
+            url: url.map(str::to_string),
+        }
+    }
+}
+
+/// A baseline entry: a fingerprint and how many diagnostics share it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BaselineEntry {
+    #[serde(flatten)]
+    fingerprint: Fingerprint,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    count: usize,
+}
+
+fn one() -> usize {
+    1
+}
+
+fn is_one(count: &usize) -> bool {
+    *count == 1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+/// Accepted diagnostics, recorded with `--write-baseline` and suppressed
+/// with `--baseline`.
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    entries: BTreeMap<Fingerprint, usize>,
+}
+
+impl Baseline {
+    /// Records the given diagnostics.
+    pub fn from_fingerprints(fingerprints: impl IntoIterator<Item = Fingerprint>) -> Self {
+        let mut entries = BTreeMap::new();
+        for fingerprint in fingerprints {
+            *entries.entry(fingerprint).or_insert(0) += 1;
+        }
+        Self { entries }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let content = fs::read_to_string(path).map_err(|source| BaselineError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let file: BaselineFile =
+            serde_json::from_str(&content).map_err(|source| BaselineError::Format {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if file.version != BASELINE_VERSION {
+            return Err(BaselineError::Version {
+                path: path.to_path_buf(),
+                version: file.version,
+            });
+        }
+        Ok(Self::from_fingerprints(file.entries.into_iter().flat_map(
+            |entry| std::iter::repeat_n(entry.fingerprint, entry.count),
+        )))
+    }
+
+    /// Writes the baseline as sorted, pretty-printed JSON so that it diffs
+    /// well under version control.
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            entries: self
+                .entries
+                .iter()
+                .map(|(fingerprint, &count)| BaselineEntry {
+                    fingerprint: fingerprint.clone(),
+                    count,
+                })
+                .collect(),
+        };
+        let content =
+            serde_json::to_string_pretty(&file).map_err(|source| BaselineError::Format {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        fs::write(path, content + "\n").map_err(|source| BaselineError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Number of recorded diagnostics.
+    pub fn len(&self) -> usize {
+        self.entries.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Starts matching the diagnostics of a run against the baseline.
+    pub fn matcher(&self) -> BaselineMatcher {
+        BaselineMatcher {
+            remaining: self.entries.clone().into_iter().collect(),
+        }
+    }
+}
+
+/// Tracks which baseline entries a run has matched.
+///
+/// Each entry suppresses as many diagnostics as it was recorded for, so a
+/// newly added copy of an accepted problem is still reported.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::baseline::{Baseline, Fingerprint};
+/// use std::path::Path;
+///
+/// let old = Fingerprint::new("NO_HTML", Path::new("a.md"), "<div>x</div>", None);
+/// let fixed = Fingerprint::new("MD012", Path::new("a.md"), "", None);
+/// let baseline = Baseline::from_fingerprints([old.clone(), fixed.clone()]);
+///
+/// let mut matcher = baseline.matcher();
+/// assert!(matcher.suppresses(&old));
+/// assert!(!matcher.suppresses(&old));
+/// assert_eq!(matcher.into_stale(), vec![(fixed, 1)]);
+/// ```
+#[derive(Debug)]
+pub struct BaselineMatcher {
+    remaining: HashMap<Fingerprint, usize>,
+}
+
+impl BaselineMatcher {
+    /// Whether a diagnostic with `fingerprint` is accepted by the baseline.
+    pub fn suppresses(&mut self, fingerprint: &Fingerprint) -> bool {
+        match self.remaining.get_mut(fingerprint) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Baseline entries that did not occur in this run, with how many of
+    /// their diagnostics are gone, sorted by fingerprint.
+    pub fn into_stale(self) -> Vec<(Fingerprint, usize)> {
+        let mut stale: Vec<_> = self
+            .remaining
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        stale.sort();
+        stale
+    }
+}
+
+/// Makes `path` relative to the current directory and drops `./`, so that
+/// the same file is recorded the same way however it was named.
+fn normalize_path(path: &Path) -> String {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .unwrap_or(path);
+    relative
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+        let html = Fingerprint::new("NO_HTML", Path::new("a.md"), "<br>", None);
+        let link = Fingerprint::new(
+            LINK_RULE_ID,
+            Path::new("a.md"),
+            "[x](https://example.com/gone)",
+            Some("https://example.com/gone"),
+        );
+        let baseline = Baseline::from_fingerprints([html.clone(), html.clone(), link.clone()]);
+
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+
+        assert_eq!(loaded.len(), 3);
+        let mut matcher = loaded.matcher();
+        assert!(matcher.suppresses(&html));
+        assert!(matcher.suppresses(&link));
+        assert_eq!(matcher.into_stale(), vec![(html, 1)]);
+    }
+
+    #[test]
+    fn test_load_rejects_other_versions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+        fs::write(&path, r#"{"version": 99, "entries": []}"#).unwrap();
+
+        assert!(matches!(
+            Baseline::load(&path),
+            Err(BaselineError::Version { version: 99, .. })
+        ));
+    }
+}
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::config::RulesConfig;
+use crate::link_checker::{CheckResult, ResultOrigin};
+use crate::rules::common::LintError;
+
+/// File inside the cache directory that stores remote link results.
+pub const LINK_CACHE_FILE: &str = "links.json";
+
+/// File inside the cache directory that stores lint diagnostics.
+pub const LINT_CACHE_FILE: &str = "lint.json";
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("failed to access cache {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("corrupt cache file {path}: {source}")]
+    Format {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+/// The last known result for a remote URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedLink {
+    pub status: u16,
+    pub error_message: Option<String>,
+    /// Seconds since the Unix epoch when the URL was checked.
+    pub checked_at: u64,
+}
+
+/// Persistent store of remote link results, shared between runs.
+///
+/// Online runs record every network result; offline runs read them back
+/// instead of making requests.
+#[derive(Debug, Default)]
+pub struct LinkCache {
+    path: PathBuf,
+    entries: HashMap<String, CachedLink>,
+    load_error: Option<CacheError>,
+}
+
+impl LinkCache {
+    /// Loads the link cache from `dir`. A missing or corrupt cache file
+    /// yields an empty cache.
+    pub fn load(dir: &Path) -> Result<Self, CacheError> {
+        let path = dir.join(LINK_CACHE_FILE);
+        let (entries, load_error) = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(entries) => (entries, None),
+                Err(source) => (
+                    HashMap::new(),
+                    Some(CacheError::Format {
+                        path: path.clone(),
+                        source,
+                    }),
+                ),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (HashMap::new(), None),
+            Err(source) => return Err(CacheError::Io { path, source }),
+        };
+
+        Ok(Self {
+            path,
+            entries,
+            load_error,
+        })
+    }
+
+    /// The parse error of a corrupt cache file that was discarded.
+    pub fn load_error(&self) -> Option<&CacheError> {
+        self.load_error.as_ref()
+    }
+
+    pub fn get(&self, url: &str) -> Option<&CachedLink> {
+        self.entries.get(url)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Records the results that came from the network. Cached, local and
+    /// unchecked results are ignored.
+    pub fn update(&mut self, results: &[CheckResult]) {
+        let checked_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        for result in results.iter().filter(|r| r.origin == ResultOrigin::Network) {
+            self.entries.insert(
+                result.link.url.clone(),
+                CachedLink {
+                    status: result.status.as_u16(),
+                    error_message: result.error_message.clone(),
+                    checked_at,
+                },
+            );
+        }
+    }
+
+    /// Writes the cache back to disk, creating the cache directory if needed.
+    pub fn save(&self) -> Result<(), CacheError> {
+        let io_error = |source| CacheError::Io {
+            path: self.path.clone(),
+            source,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let content =
+            serde_json::to_string_pretty(&self.entries).map_err(|source| CacheError::Format {
+                path: self.path.clone(),
+                source,
+            })?;
+        fs::write(&self.path, content).map_err(io_error)
+    }
+}
+
+/// Diagnostics of one file, valid while its content hash matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedLint {
+    content_hash: String,
+    errors: Vec<LintError>,
+}
+
+/// On-disk layout of [`LintCache`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LintCacheFile {
+    version: String,
+    config_hash: String,
+    files: HashMap<PathBuf, CachedLint>,
+}
+
+/// Persistent store of lint diagnostics keyed by file content.
+///
+/// The whole cache is discarded when it was written by another md-check
+/// version or with a different rule configuration, so stale diagnostics
+/// are never reported.
+#[derive(Debug, Default)]
+pub struct LintCache {
+    path: PathBuf,
+    data: LintCacheFile,
+    invalidated: bool,
+    load_error: Option<CacheError>,
+    /// Files looked up or recorded during this run; only these are saved.
+    seen: HashSet<PathBuf>,
+}
+
+impl LintCache {
+    /// Loads the lint cache from `dir` for the given rule configuration.
+    /// A missing or corrupt cache file yields an empty cache.
+    pub fn load(dir: &Path, rules: &RulesConfig) -> Result<Self, CacheError> {
+        let path = dir.join(LINT_CACHE_FILE);
+        let version = env!("CARGO_PKG_VERSION").to_string();
+        let config_hash =
+            hash(
+                &serde_json::to_vec(rules).map_err(|source| CacheError::Format {
+                    path: path.clone(),
+                    source,
+                })?,
+            );
+
+        let (stored, load_error) = match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<LintCacheFile>(&content) {
+                Ok(data) => (Some(data), None),
+                Err(source) => (
+                    None,
+                    Some(CacheError::Format {
+                        path: path.clone(),
+                        source,
+                    }),
+                ),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (None, None),
+            Err(source) => return Err(CacheError::Io { path, source }),
+        };
+
+        let invalidated = stored
+            .as_ref()
+            .is_some_and(|data| data.version != version || data.config_hash != config_hash);
+        let files = match stored {
+            Some(data) if !invalidated => data.files,
+            _ => HashMap::new(),
+        };
+
+        Ok(Self {
+            path,
+            data: LintCacheFile {
+                version,
+                config_hash,
+                files,
+            },
+            invalidated,
+            load_error,
+            seen: HashSet::new(),
+        })
+    }
+
+    /// The parse error of a corrupt cache file that was discarded.
+    pub fn load_error(&self) -> Option<&CacheError> {
+        self.load_error.as_ref()
+    }
+
+    /// Whether a cache file existed but was discarded because the md-check
+    /// version or the rule configuration changed.
+    pub fn invalidated(&self) -> bool {
+        self.invalidated
+    }
+
+    /// Returns the cached diagnostics for `file_path` if `content` is
+    /// unchanged since they were recorded.
+    pub fn get(&mut self, file_path: &Path, content: &str) -> Option<&[LintError]> {
+        self.seen.insert(file_path.to_path_buf());
+        self.data
+            .files
+            .get(file_path)
+            .filter(|cached| cached.content_hash == hash(content.as_bytes()))
+            .map(|cached| cached.errors.as_slice())
+    }
+
+    /// Records the diagnostics of `file_path` for its current `content`.
+    pub fn insert(&mut self, file_path: &Path, content: &str, errors: &[LintError]) {
+        self.seen.insert(file_path.to_path_buf());
+        self.data.files.insert(
+            file_path.to_path_buf(),
+            CachedLint {
+                content_hash: hash(content.as_bytes()),
+                errors: errors.to_vec(),
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.files.is_empty()
+    }
+
+    /// Writes the cache back to disk, creating the cache directory if needed.
+    /// Files that were not linted in this run are dropped, so entries of
+    /// deleted or renamed files do not pile up.
+    pub fn save(mut self) -> Result<(), CacheError> {
+        let seen = &self.seen;
+        self.data.files.retain(|path, _| seen.contains(path));
+        let io_error = |source| CacheError::Io {
+            path: self.path.clone(),
+            source,
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let content = serde_json::to_string(&self.data).map_err(|source| CacheError::Format {
+            path: self.path.clone(),
+            source,
+        })?;
+        fs::write(&self.path, content).map_err(io_error)
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lint_error(file_path: &Path) -> LintError {
+        LintError {
+            file_path: file_path.to_path_buf(),
+            line: 3,
+            column: None,
+            message: "Multiple consecutive blank lines".to_string(),
+            rule_id: "MD012".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lint_cache_hits_unchanged_content() {
+        let dir = TempDir::new().unwrap();
+        let file = Path::new("docs/guide.md");
+        let rules = RulesConfig::default();
+
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+        cache.insert(file, "# Guide\n", &[lint_error(file)]);
+        cache.save().unwrap();
+
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+        assert!(!cache.invalidated());
+        assert_eq!(cache.get(file, "# Guide\n"), Some(&[lint_error(file)][..]));
+        assert_eq!(cache.get(file, "# Changed guide\n"), None);
+        assert_eq!(cache.get(Path::new("other.md"), "# Guide\n"), None);
+    }
+
+    #[test]
+    fn test_lint_cache_invalidated_by_rule_config() {
+        let dir = TempDir::new().unwrap();
+        let file = Path::new("guide.md");
+
+        let mut cache = LintCache::load(dir.path(), &RulesConfig::default()).unwrap();
+        cache.insert(file, "# Guide\n", &[]);
+        cache.save().unwrap();
+
+        let mut rules = RulesConfig::default();
+        rules.url_hygiene.tracking_params = false;
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+
+        assert!(cache.invalidated());
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(file, "# Guide\n"), None);
+    }
+
+    #[test]
+    fn test_link_cache_discards_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(LINK_CACHE_FILE), "not json at all").unwrap();
+
+        let cache = LinkCache::load(dir.path()).unwrap();
+
+        assert!(matches!(
+            cache.load_error(),
+            Some(CacheError::Format { .. })
+        ));
+        assert!(cache.is_empty());
+        cache.save().unwrap();
+        let cache = LinkCache::load(dir.path()).unwrap();
+        assert!(cache.load_error().is_none());
+    }
+
+    #[test]
+    fn test_lint_cache_discards_corrupt_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(LINT_CACHE_FILE), "{\"version\": \"0.1").unwrap();
+
+        let cache = LintCache::load(dir.path(), &RulesConfig::default()).unwrap();
+
+        assert!(matches!(
+            cache.load_error(),
+            Some(CacheError::Format { .. })
+        ));
+        assert!(cache.is_empty());
+        cache.save().unwrap();
+        let cache = LintCache::load(dir.path(), &RulesConfig::default()).unwrap();
+        assert!(cache.load_error().is_none());
+    }
+
+    #[test]
+    fn test_lint_cache_prunes_unseen_files() {
+        let dir = TempDir::new().unwrap();
+        let rules = RulesConfig::default();
+
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+        cache.insert(Path::new("kept.md"), "# Kept\n", &[]);
+        cache.insert(Path::new("renamed.md"), "# Renamed\n", &[]);
+        cache.save().unwrap();
+
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+        assert!(cache.get(Path::new("kept.md"), "# Kept\n").is_some());
+        cache.save().unwrap();
+
+        let mut cache = LintCache::load(dir.path(), &rules).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(Path::new("renamed.md"), "# Renamed\n"), None);
+    }
+}
@@ -0,0 +1,704 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Name of the configuration file looked up in the working directory and its parents.
+pub const CONFIG_FILE_NAME: &str = ".md-check.toml";
+
+/// Commented starting point written by `md-check init`. Every setting shows
+/// its default value.
+pub const STARTER_CONFIG: &str = r#"# md-check configuration, see https://github.com/eugenescodes/md-check
+
+[files]
+# extensions = ["md", "markdown", "mdown", "mkd", "mkdn", "mdwn"]
+# include = []
+# exclude = []
+# respect_ignore_files = true
+# hidden = false
+
+[links]
+# timeout = 30
+# allowed_schemes = ["http", "https", "mailto", "tel", "ftp"]
+
+[cache]
+# enabled = false
+# dir = ".md-check-cache"
+
+[rules]
+# Rule IDs, aliases such as MD013, or tags: whitespace, links, headings, lists,
+# html, security, accessibility, line_length. `md-check rules` lists them.
+# enable = []
+# disable = []
+
+[rules.url_hygiene]
+# trailing_punctuation = true
+# nested_markdown = true
+# whitespace = true
+# local_addresses = true
+# insecure_http = false   # probes the https:// variant of every http:// link
+# tracking_params = true
+# extra_tracking_params = []
+
+[rules.line_endings]
+# style = "consistent"
+
+[rules.line_too_long]
+# line_length = 100
+# code_block_line_length = 100
+# heading_line_length = 100
+# code_blocks = true
+# headings = true
+# tables = true
+# strict = false
+
+[rules.no_html]
+# allowed_elements = []
+# forbidden_elements = []
+# allow_comments = true
+
+[rules.md003]
+# style = "consistent"   # or "atx", "atx_closed", "setext", "setext_with_atx" or "setext_with_atx_closed"
+
+[rules.md004]
+# style = "consistent"   # or "asterisk", "dash", "plus" or "sublist"
+
+[rules.md007]
+# indent = 2
+
+[rules.md024]
+# siblings_only = false
+# ignore_case = false
+# ignore_punctuation = false
+
+[rules.md025]
+# level = 1
+# front_matter_title = "heading"   # or "match" or "ignore"
+
+[rules.md026]
+# punctuation = ".,;:!。，；：！"
+
+[rules.md029]
+# style = "one_or_ordered"   # or "one", "ordered" or "zero"
+
+[rules.md030]
+# ul_single = 1
+# ol_single = 1
+# ul_multi = 1
+# ol_multi = 1
+
+[rules.md041]
+# level = 1
+# front_matter_title = true
+"#;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Top-level configuration loaded from `.md-check.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub files: FilesConfig,
+    pub links: LinksConfig,
+    pub cache: CacheConfig,
+    pub rules: RulesConfig,
+}
+
+/// Settings for finding Markdown files inside the given directories.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilesConfig {
+    /// Extensions of Markdown files, matched case-insensitively.
+    pub extensions: Vec<String>,
+    /// Names of extensionless files that are Markdown, such as `README`.
+    pub file_names: Vec<String>,
+    /// Only check files matching one of these globs (all files if empty).
+    pub include: Vec<String>,
+    /// Skip files and directories matching any of these globs.
+    pub exclude: Vec<String>,
+    /// Honor `.gitignore`, `.ignore` and git exclude files.
+    pub respect_ignore_files: bool,
+    /// Descend into hidden directories and check hidden files.
+    pub hidden: bool,
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            extensions: ["md", "markdown", "mdown", "mkd", "mkdn", "mdwn"]
+                .map(String::from)
+                .to_vec(),
+            file_names: vec!["README".to_string()],
+            include: Vec::new(),
+            exclude: Vec::new(),
+            respect_ignore_files: true,
+            hidden: false,
+        }
+    }
+}
+
+/// Settings for the HTTP client used during link checking.
+///
+/// Timeouts are given in seconds. Unset values fall back to the defaults
+/// of [`crate::link_checker::ClientOptions`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinksConfig {
+    pub timeout: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub read_timeout: Option<u64>,
+    pub user_agent: Option<String>,
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub no_proxy: Vec<String>,
+    pub ca_bundle: Option<PathBuf>,
+    pub insecure_hosts: Vec<String>,
+    /// URL schemes links may use; defaults to http, https, mailto, tel and ftp.
+    pub allowed_schemes: Option<Vec<String>>,
+}
+
+/// Settings for the persistent cache directory.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Record link results so that later `--offline` runs can reuse them,
+    /// and skip linting files whose content has not changed.
+    pub enabled: bool,
+    pub dir: PathBuf,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from(".md-check-cache"),
+        }
+    }
+}
+
+/// Per-rule settings, one table per configurable rule.
+///
+/// Tables are named after the rule ID in lowercase, such as `md003` or
+/// `line_too_long`. The aliases of the rule, like `heading-style` or `md013`,
+/// are accepted as well.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    /// Rule IDs, aliases or tags to run even if disabled by default.
+    pub enable: Vec<String>,
+    /// Rule IDs, aliases or tags not to run. A rule named directly in one
+    /// list overrides a tag in the other.
+    pub disable: Vec<String>,
+    pub url_hygiene: UrlHygieneConfig,
+    pub line_endings: LineEndingsConfig,
+    #[serde(alias = "md013", alias = "line-length")]
+    pub line_too_long: LineTooLongConfig,
+    #[serde(alias = "md033", alias = "no-inline-html")]
+    pub no_html: NoHtmlConfig,
+    #[serde(alias = "heading-style")]
+    pub md003: HeadingStyleConfig,
+    #[serde(alias = "ul-style")]
+    pub md004: UnorderedListStyleConfig,
+    #[serde(alias = "ul-indent")]
+    pub md007: UnorderedListIndentConfig,
+    #[serde(alias = "no-duplicate-heading", alias = "no-duplicate-header")]
+    pub md024: DuplicateHeadingConfig,
+    #[serde(alias = "single-title", alias = "single-h1")]
+    pub md025: SingleTitleConfig,
+    #[serde(alias = "no-trailing-punctuation")]
+    pub md026: TrailingPunctuationConfig,
+    #[serde(alias = "ol-prefix")]
+    pub md029: OrderedListPrefixConfig,
+    #[serde(alias = "list-marker-space")]
+    pub md030: ListMarkerSpaceConfig,
+    #[serde(alias = "first-line-heading", alias = "first-line-h1")]
+    pub md041: FirstLineHeadingConfig,
+}
+
+impl RulesConfig {
+    /// Enables `name`, a rule ID, alias or tag, replacing an earlier choice
+    /// to disable it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::config::RulesConfig;
+    ///
+    /// let mut rules = RulesConfig::default();
+    /// rules.choose_disabled("MD013");
+    /// rules.choose_enabled("LINE_TOO_LONG");
+    ///
+    /// assert!(rules.disable.is_empty());
+    /// assert_eq!(rules.enable, vec!["LINE_TOO_LONG"]);
+    /// ```
+    pub fn choose_enabled(&mut self, name: &str) {
+        self.disable.retain(|n| !same_name(n, name));
+        self.enable.push(name.to_string());
+    }
+
+    /// Disables `name`, replacing an earlier choice to enable it.
+    pub fn choose_disabled(&mut self, name: &str) {
+        self.enable.retain(|n| !same_name(n, name));
+        self.disable.push(name.to_string());
+    }
+
+    /// Applies the settings of a `[rules]` table on top of these ones. Its
+    /// options replace ours one by one, and its `enable` and `disable`
+    /// entries replace our opposite choices.
+    fn overlay(self, overrides: toml::Table) -> Result<Self, toml::de::Error> {
+        let choices: RulesConfig = toml::Value::Table(overrides.clone()).try_into()?;
+        // Tables named by an alias must meet ours, which use the rule ID
+        let overrides = overrides
+            .into_iter()
+            .map(|(key, value)| match crate::rules::find_rule(&key) {
+                Some(rule) if value.is_table() => (rule.id().to_ascii_lowercase(), value),
+                _ => (key, value),
+            })
+            .collect();
+        let mut merged = toml::Table::try_from(&self).expect("rule settings serialize to TOML");
+        merge_tables(&mut merged, overrides);
+
+        let mut rules: RulesConfig = toml::Value::Table(merged).try_into()?;
+        rules.enable = self.enable;
+        rules.disable = self.disable;
+        for name in &choices.enable {
+            rules.choose_enabled(name);
+        }
+        for name in &choices.disable {
+            rules.choose_disabled(name);
+        }
+        Ok(rules)
+    }
+}
+
+/// Whether two rule or tag names refer to the same thing, such as `MD013`
+/// and `LINE_TOO_LONG`.
+fn same_name(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+        || matches!(
+            (crate::rules::find_rule(a), crate::rules::find_rule(b)),
+            (Some(a), Some(b)) if a.id() == b.id()
+        )
+}
+
+/// Recursively copies the entries of `overrides` into `base`.
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge_tables(base, overrides)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Settings for the `MD003` rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeadingStyleConfig {
+    pub style: HeadingStyleOption,
+}
+
+/// Heading style required by the `MD003` rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadingStyleOption {
+    /// Every heading must be written like the first one.
+    #[default]
+    Consistent,
+    Atx,
+    AtxClosed,
+    /// Setext for levels 1 and 2, which are the only ones it can express.
+    /// Deeper headings may use either ATX style.
+    Setext,
+    /// Setext for levels 1 and 2, and ATX below.
+    SetextWithAtx,
+    /// Setext for levels 1 and 2, and closed ATX below.
+    SetextWithAtxClosed,
+}
+
+/// Settings for the `MD004` rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnorderedListStyleConfig {
+    pub style: UnorderedListStyle,
+}
+
+/// Marker required by the `MD004` rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnorderedListStyle {
+    /// Every item must use the marker of the first one.
+    #[default]
+    Consistent,
+    Asterisk,
+    Dash,
+    Plus,
+    /// Each nesting level keeps one marker, different from its parent's.
+    Sublist,
+}
+
+/// Settings for the `MD007` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnorderedListIndentConfig {
+    /// Spaces per nesting level.
+    pub indent: usize,
+}
+
+impl Default for UnorderedListIndentConfig {
+    fn default() -> Self {
+        Self { indent: 2 }
+    }
+}
+
+/// Settings for the `MD024` rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicateHeadingConfig {
+    /// Only compare headings under the same parent heading.
+    pub siblings_only: bool,
+    pub ignore_case: bool,
+    /// Compare headings without ASCII punctuation.
+    pub ignore_punctuation: bool,
+}
+
+/// Settings for the `MD025` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SingleTitleConfig {
+    /// Level of the heading that may appear only once.
+    pub level: u8,
+    pub front_matter_title: FrontMatterTitle,
+}
+
+impl Default for SingleTitleConfig {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            front_matter_title: FrontMatterTitle::default(),
+        }
+    }
+}
+
+/// Settings for the `MD026` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrailingPunctuationConfig {
+    /// Characters a heading may not end with.
+    pub punctuation: String,
+}
+
+impl Default for TrailingPunctuationConfig {
+    fn default() -> Self {
+        Self {
+            punctuation: ".,;:!。，；：！".to_string(),
+        }
+    }
+}
+
+/// Settings for the `MD029` rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrderedListPrefixConfig {
+    pub style: OrderedListStyle,
+}
+
+/// Numbering required by the `MD029` rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderedListStyle {
+    /// `one` or `zero` if the first two items have the same number 1 or 0,
+    /// `ordered` otherwise.
+    #[default]
+    OneOrOrdered,
+    /// Every item is numbered 1.
+    One,
+    /// Items count up from the number of the first one.
+    Ordered,
+    /// Every item is numbered 0.
+    Zero,
+}
+
+/// Settings for the `MD030` rule: spaces after the marker of items in
+/// lists whose items are single paragraphs, and in loose lists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListMarkerSpaceConfig {
+    pub ul_single: usize,
+    pub ol_single: usize,
+    pub ul_multi: usize,
+    pub ol_multi: usize,
+}
+
+impl Default for ListMarkerSpaceConfig {
+    fn default() -> Self {
+        Self {
+            ul_single: 1,
+            ol_single: 1,
+            ul_multi: 1,
+            ol_multi: 1,
+        }
+    }
+}
+
+/// How the `MD025` rule treats a `title` in front matter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterTitle {
+    /// The title is the document's top-level heading, so a heading of that
+    /// level is a second one.
+    #[default]
+    Heading,
+    /// The top-level heading must repeat the title.
+    Match,
+    Ignore,
+}
+
+/// Settings for the `MD041` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FirstLineHeadingConfig {
+    /// Level of the heading the document must start with.
+    pub level: u8,
+    /// Accept a document without that heading if its front matter has a
+    /// `title`.
+    pub front_matter_title: bool,
+}
+
+impl Default for FirstLineHeadingConfig {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            front_matter_title: true,
+        }
+    }
+}
+
+/// Settings for the `NO_HTML` rule. Element names are matched
+/// case-insensitively.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NoHtmlConfig {
+    /// Elements that are never reported, such as `details` or `br`.
+    pub allowed_elements: Vec<String>,
+    /// If not empty, only these elements are reported.
+    pub forbidden_elements: Vec<String>,
+    /// Accept HTML comments.
+    pub allow_comments: bool,
+}
+
+impl Default for NoHtmlConfig {
+    fn default() -> Self {
+        Self {
+            allowed_elements: Vec::new(),
+            forbidden_elements: Vec::new(),
+            allow_comments: true,
+        }
+    }
+}
+
+/// Settings for the `LINE_TOO_LONG` rule. Lengths are display columns, so
+/// wide characters such as CJK and emoji count twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LineTooLongConfig {
+    /// Maximum width of prose and table lines.
+    pub line_length: usize,
+    /// Maximum width of code block lines; `line_length` if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_block_line_length: Option<usize>,
+    /// Maximum width of headings; `line_length` if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_line_length: Option<usize>,
+    /// Check code block lines.
+    pub code_blocks: bool,
+    /// Check headings.
+    pub headings: bool,
+    /// Check table rows.
+    pub tables: bool,
+    /// Also report lines that are only too long because they end in a
+    /// link that cannot be wrapped.
+    pub strict: bool,
+}
+
+impl Default for LineTooLongConfig {
+    fn default() -> Self {
+        Self {
+            line_length: 100,
+            code_block_line_length: None,
+            heading_line_length: None,
+            code_blocks: true,
+            headings: true,
+            tables: true,
+            strict: false,
+        }
+    }
+}
+
+/// Settings for the `LINE_ENDINGS` rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LineEndingsConfig {
+    pub style: LineEndingStyle,
+}
+
+/// Line terminator required by the `LINE_ENDINGS` rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndingStyle {
+    /// Every line must end like the first line of the file.
+    #[default]
+    Consistent,
+    Lf,
+    Crlf,
+}
+
+/// Categories checked by the `URL_HYGIENE` rule. All but `insecure_http`
+/// are enabled by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UrlHygieneConfig {
+    /// Trailing `.`, `,` and similar characters, and unbalanced parentheses.
+    pub trailing_punctuation: bool,
+    /// Markdown syntax such as `[text](url)` inside the URL.
+    pub nested_markdown: bool,
+    /// Spaces, tabs or line breaks inside the URL.
+    pub whitespace: bool,
+    /// `localhost`, loopback and private network addresses.
+    pub local_addresses: bool,
+    /// `http://` links whose `https://` variant works. Needs link checking
+    /// and costs an extra request per `http://` link, so it is opt-in.
+    pub insecure_http: bool,
+    /// `utm_*`, `fbclid` and other tracking query parameters.
+    pub tracking_params: bool,
+    /// Additional query parameter names treated as tracking parameters.
+    pub extra_tracking_params: Vec<String>,
+}
+
+impl Default for UrlHygieneConfig {
+    fn default() -> Self {
+        Self {
+            trailing_punctuation: true,
+            nested_markdown: true,
+            whitespace: true,
+            local_addresses: true,
+            insecure_http: false,
+            tracking_params: true,
+            extra_tracking_params: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Parses a configuration from a TOML string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::config::Config;
+    ///
+    /// let config = Config::parse("[links]\ntimeout = 5\n").unwrap();
+    ///
+    /// assert_eq!(config.links.timeout, Some(5));
+    /// ```
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Parses a configuration whose `[rules]` settings apply on top of
+    /// `rules`, such as those read from a markdownlint file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::config::{Config, RulesConfig};
+    ///
+    /// let mut base = RulesConfig::default();
+    /// base.choose_disabled("NO_HTML");
+    /// base.line_too_long.line_length = 120;
+    ///
+    /// let config = Config::parse_over("[rules]\nenable = [\"MD033\"]\n", base).unwrap();
+    ///
+    /// assert_eq!(config.rules.enable, vec!["MD033"]);
+    /// assert!(config.rules.disable.is_empty());
+    /// assert_eq!(config.rules.line_too_long.line_length, 120);
+    ///
+    /// // Rule tables may be named by any alias of the rule
+    /// let config = Config::parse_over("[rules.md013]\ncode_blocks = false\n", config.rules).unwrap();
+    ///
+    /// assert!(!config.rules.line_too_long.code_blocks);
+    /// assert_eq!(config.rules.line_too_long.line_length, 120);
+    /// ```
+    pub fn parse_over(content: &str, rules: RulesConfig) -> Result<Self, toml::de::Error> {
+        // Parse once as a whole for errors that point into the file
+        let mut config = Self::parse(content)?;
+        let mut table: toml::Table = toml::from_str(content)?;
+        config.rules = match table.remove("rules") {
+            Some(toml::Value::Table(overrides)) => rules.overlay(overrides)?,
+            _ => rules,
+        };
+        Ok(config)
+    }
+
+    /// Loads the configuration from the given file.
+    ///
+    /// Relative paths inside the file (such as `ca_bundle`) are resolved
+    /// against the directory that contains it.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        Self::load_over(path, RulesConfig::default())
+    }
+
+    /// Loads the configuration from the given file, with its `[rules]`
+    /// settings applied on top of `rules`.
+    pub fn load_over(path: &Path, rules: RulesConfig) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut config =
+            Self::parse_over(&content, rules).map_err(|source| ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        if let Some(base) = path.parent() {
+            config.resolve_paths(base);
+        }
+        Ok(config)
+    }
+
+    /// Searches `start` and its ancestors for `.md-check.toml`.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    fn resolve_paths(&mut self, base: &Path) {
+        if let Some(ca_bundle) = &self.links.ca_bundle
+            && ca_bundle.is_relative()
+        {
+            self.links.ca_bundle = Some(base.join(ca_bundle));
+        }
+        if self.cache.dir.is_relative() {
+            self.cache.dir = base.join(&self.cache.dir);
+        }
+    }
+}
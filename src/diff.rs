@@ -0,0 +1,313 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Matches the new-file side of a unified diff hunk header, `@@ -a,b +c,d @@`.
+static HUNK_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,(\d+))? @@").unwrap());
+
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("failed to run git: {0}")]
+    Io(#[from] io::Error),
+    #[error("`git {command}` failed: {message}")]
+    Git { command: String, message: String },
+}
+
+/// What to compare the working tree against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffTarget {
+    /// Changes since a commit, branch or tag, including uncommitted and
+    /// untracked files.
+    Base(String),
+    /// Changes staged in the index, as a pre-commit hook sees them.
+    Staged,
+}
+
+/// Lines that changed in a git diff, by file.
+#[derive(Debug, Clone, Default)]
+pub struct ChangedLines {
+    /// Keyed by canonical path.
+    files: HashMap<PathBuf, FileChanges>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FileChanges {
+    /// A file git does not track yet.
+    All,
+    /// Inclusive, 1-based ranges of added or modified lines.
+    Ranges(Vec<(usize, usize)>),
+}
+
+impl ChangedLines {
+    /// Asks git in the repository containing `dir` which lines changed.
+    pub fn from_git(dir: &Path, target: &DiffTarget) -> Result<Self, DiffError> {
+        let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim_end());
+
+        // Override user settings that change the output format
+        let mut args = vec![
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--unified=0",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ];
+        match target {
+            DiffTarget::Base(base) => args.extend([base.as_str(), "--"]),
+            DiffTarget::Staged => args.push("--cached"),
+        }
+        let mut changed = Self::parse(&root, &git(&root, &args)?);
+
+        if let DiffTarget::Base(_) = target {
+            let untracked = git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+            for file in untracked.split('\0').filter(|file| !file.is_empty()) {
+                changed
+                    .files
+                    .insert(canonical(&root.join(file)), FileChanges::All);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Collects the added and modified lines of a unified diff whose paths
+    /// are relative to `root`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::diff::ChangedLines;
+    /// use std::path::Path;
+    ///
+    /// let diff = "\
+    /// diff --git a/docs/guide.md b/docs/guide.md
+    /// --- a/docs/guide.md
+    /// +++ b/docs/guide.md
+    /// @@ -3,0 +4,2 @@ Intro
+    /// +New paragraph
+    /// +
+    /// ";
+    /// let changed = ChangedLines::parse(Path::new("/repo"), diff);
+    ///
+    /// assert!(changed.contains(Path::new("/repo/docs/guide.md"), 5));
+    /// assert!(!changed.contains(Path::new("/repo/docs/guide.md"), 6));
+    /// assert!(!changed.contains_file(Path::new("/repo/README.md")));
+    /// ```
+    pub fn parse(root: &Path, diff: &str) -> Self {
+        let root = canonical(root);
+        let mut files = HashMap::new();
+        let mut ranges: Option<&mut Vec<(usize, usize)>> = None;
+        // Between `diff --git` and the first hunk; an added line starting
+        // with `++ ` must not be taken for a file name
+        let mut in_header = false;
+
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                in_header = true;
+                ranges = None;
+            } else if in_header && let Some(header) = line.strip_prefix("+++ ") {
+                // Deleted files have no new lines
+                ranges = new_path(header).map(|path| {
+                    match files
+                        .entry(root.join(path))
+                        .or_insert_with(|| FileChanges::Ranges(Vec::new()))
+                    {
+                        FileChanges::Ranges(ranges) => ranges,
+                        FileChanges::All => unreachable!("diffs only list tracked files"),
+                    }
+                });
+            } else if let Some(captures) = HUNK_HEADER.captures(line) {
+                in_header = false;
+                let Some(ranges) = ranges.as_deref_mut() else {
+                    continue;
+                };
+                let start: usize = captures[1].parse().unwrap_or(0);
+                let count: usize = captures
+                    .get(2)
+                    .map_or(1, |count| count.as_str().parse().unwrap_or(0));
+                // Pure deletions leave no line to report on
+                if count > 0 {
+                    ranges.push((start, start + count - 1));
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Whether `path` has any changes.
+    pub fn contains_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&canonical(path))
+    }
+
+    /// Whether the 1-based `line` of `path` was added or modified.
+    pub fn contains(&self, path: &Path, line: usize) -> bool {
+        match self.files.get(&canonical(path)) {
+            Some(FileChanges::All) => true,
+            Some(FileChanges::Ranges(ranges)) => ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&line)),
+            None => false,
+        }
+    }
+}
+
+/// The content of `path` as staged in the index, which `--staged` lints
+/// instead of the working tree copy.
+pub fn staged_content(path: &Path) -> Result<Vec<u8>, DiffError> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    git_bytes(
+        dir.unwrap_or(Path::new(".")),
+        &["show", &format!(":./{}", name)],
+    )
+}
+
+/// The path in the `+++ ` line of a diff, without the `b/` prefix, or
+/// `None` for `/dev/null`. git appends a tab to names with spaces and
+/// C-quotes names with special characters.
+fn new_path(header: &str) -> Option<String> {
+    let header = header.strip_suffix('\t').unwrap_or(header);
+    let path = match header.strip_prefix('"') {
+        Some(quoted) => unquote(quoted.strip_suffix('"')?),
+        None => header.to_string(),
+    };
+    path.strip_prefix("b/").map(str::to_string)
+}
+
+/// Undoes git's C-style quoting of a path, including octal escapes of
+/// UTF-8 bytes.
+fn unquote(quoted: &str) -> String {
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut rest = quoted.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let Some((&escape, tail)) = rest.split_first() else {
+            break;
+        };
+        rest = tail;
+        bytes.push(match escape {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0b,
+            // Always three digits, as git writes them
+            b'0'..=b'3' if rest.len() >= 2 => {
+                let value = rest[..2]
+                    .iter()
+                    .fold(escape - b'0', |value, digit| value * 8 + (digit - b'0'));
+                rest = &rest[2..];
+                value
+            }
+            other => other,
+        });
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, DiffError> {
+    Ok(String::from_utf8_lossy(&git_bytes(dir, args)?).into_owned())
+}
+
+fn git_bytes(dir: &Path, args: &[&str]) -> Result<Vec<u8>, DiffError> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(DiffError::Git {
+            command: args.join(" "),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Resolves symlinks and relative components so that paths from git compare
+/// equal to discovered ones. Paths that do not exist are kept as they are.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_hunks() {
+        let diff = "\
+diff --git a/a.md b/a.md
+--- a/a.md
++++ b/a.md
+@@ -1 +1 @@
+-old
++new
+@@ -10,3 +10,0 @@
+@@ -20,0 +18,3 @@
++x
++y
++z
+diff --git a/gone.md b/gone.md
+--- a/gone.md
++++ /dev/null
+@@ -1,2 +0,0 @@
+";
+        let changed = ChangedLines::parse(Path::new("/repo"), diff);
+        let a = Path::new("/repo/a.md");
+
+        assert!(changed.contains(a, 1));
+        assert!(!changed.contains(a, 2));
+        assert!(!changed.contains(a, 10));
+        assert!(changed.contains(a, 18));
+        assert!(changed.contains(a, 20));
+        assert!(!changed.contains(a, 21));
+        assert!(!changed.contains_file(Path::new("/repo/gone.md")));
+    }
+
+    #[test]
+    fn test_parse_quoted_paths_and_added_header_lines() {
+        let diff = "\
+diff --git a/my doc.md b/my doc.md
+--- a/my doc.md\t
++++ b/my doc.md\t
+@@ -1,0 +2 @@
++text
+diff --git \"a/caf\\303\\251 \\\"x\\\".md\" \"b/caf\\303\\251 \\\"x\\\".md\"
+--- \"a/caf\\303\\251 \\\"x\\\".md\"
++++ \"b/caf\\303\\251 \\\"x\\\".md\"
+@@ -1 +1,2 @@
+-- old
+-ok
++++ b/other.md
++@@ -1 +40 @@
+";
+        let changed = ChangedLines::parse(Path::new("/repo"), diff);
+        let quoted = Path::new("/repo/caf\u{e9} \"x\".md");
+
+        assert!(changed.contains(Path::new("/repo/my doc.md"), 2));
+        assert!(changed.contains(quoted, 2));
+        assert!(!changed.contains(quoted, 40));
+        assert!(!changed.contains_file(Path::new("/repo/other.md")));
+    }
+
+    #[test]
+    fn test_from_git_outside_repository() {
+        let dir = TempDir::new().unwrap();
+        let error = ChangedLines::from_git(dir.path(), &DiffTarget::Staged).unwrap_err();
+
+        assert!(
+            matches!(error, DiffError::Git { command, .. } if command.starts_with("rev-parse"))
+        );
+    }
+}
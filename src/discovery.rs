@@ -0,0 +1,323 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+use crate::config::FilesConfig;
+
+#[derive(Debug, Error)]
+pub enum DiscoveryError {
+    #[error("invalid glob pattern '{pattern}': {source}")]
+    Glob {
+        pattern: String,
+        source: globset::Error,
+    },
+}
+
+/// Finds the Markdown files to check under the paths given on the command line.
+///
+/// Include and exclude globs are matched against the path relative to the
+/// directory being walked and against the bare file name, so `CHANGELOG.md`
+/// excludes every changelog while `docs/generated/**` excludes one subtree.
+#[derive(Debug, Clone)]
+pub struct FileDiscovery {
+    extensions: Vec<String>,
+    file_names: Vec<String>,
+    include: GlobSet,
+    exclude: GlobSet,
+    respect_ignore_files: bool,
+    hidden: bool,
+}
+
+impl FileDiscovery {
+    pub fn new(config: &FilesConfig) -> Result<Self, DiscoveryError> {
+        Ok(Self {
+            extensions: config
+                .extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_string())
+                .collect(),
+            file_names: config.file_names.clone(),
+            include: build_glob_set(&config.include)?,
+            exclude: build_glob_set(&config.exclude)?,
+            respect_ignore_files: config.respect_ignore_files,
+            hidden: config.hidden,
+        })
+    }
+
+    /// Calls `found` with every Markdown file under `paths`, in a stable order.
+    ///
+    /// Directories are walked honoring ignore files and skipping hidden
+    /// entries unless configured otherwise, and only files with a Markdown
+    /// extension are picked up. Files named directly are always checked,
+    /// whatever their extension; only exclude globs apply to them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::config::FilesConfig;
+    /// use md_check::discovery::FileDiscovery;
+    /// use std::path::PathBuf;
+    ///
+    /// let config = FilesConfig {
+    ///     exclude: vec!["CHANGELOG.md".to_string()],
+    ///     ..FilesConfig::default()
+    /// };
+    /// let discovery = FileDiscovery::new(&config).unwrap();
+    ///
+    /// let mut files = Vec::new();
+    /// discovery.discover(
+    ///     &[PathBuf::from("README.md"), PathBuf::from("CHANGELOG.md")],
+    ///     |path| files.push(path),
+    /// );
+    ///
+    /// assert_eq!(files, vec![PathBuf::from("README.md")]);
+    /// assert!(discovery.is_markdown(&PathBuf::from("docs/Guide.MARKDOWN")));
+    /// ```
+    pub fn discover(&self, paths: &[PathBuf], mut found: impl FnMut(PathBuf)) {
+        for path in paths {
+            if path.is_dir() {
+                self.walk(path, &mut found);
+            } else if path.is_file() && !matches(&self.exclude, &normalize(path)) {
+                found(path.clone());
+            }
+        }
+    }
+
+    /// Whether `path` has one of the configured Markdown extensions, or is an
+    /// extensionless file with one of the configured names.
+    pub fn is_markdown(&self, path: &Path) -> bool {
+        let eq = |a: &std::ffi::OsStr, b: &String| a.eq_ignore_ascii_case(b);
+        match path.extension() {
+            Some(ext) => self.extensions.iter().any(|e| eq(ext, e)),
+            None => path
+                .file_name()
+                .is_some_and(|name| self.file_names.iter().any(|n| eq(name, n))),
+        }
+    }
+
+    fn walk(&self, root: &Path, found: &mut impl FnMut(PathBuf)) {
+        let exclude = self.exclude.clone();
+        let prune_root = root.to_path_buf();
+
+        let walker = WalkBuilder::new(root)
+            .standard_filters(self.respect_ignore_files)
+            .hidden(!self.hidden)
+            // Ignore files apply even when the docs are not in a git checkout
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                entry.depth() == 0 || !matches(&exclude, relative(entry.path(), &prune_root))
+            })
+            .build();
+
+        for entry in walker.filter_map(Result::ok) {
+            if entry.file_type().is_none_or(|t| t.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            if !self.is_markdown(path) {
+                continue;
+            }
+            if !self.include.is_empty() && !matches(&self.include, relative(path, root)) {
+                continue;
+            }
+            found(path.to_path_buf());
+        }
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, DiscoveryError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|source| DiscoveryError::Glob {
+                pattern: pattern.clone(),
+                source,
+            })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|source| DiscoveryError::Glob {
+        pattern: patterns.join(", "),
+        source,
+    })
+}
+
+/// Whether `path` or its file name matches any glob in `set`.
+fn matches(set: &GlobSet, path: &Path) -> bool {
+    set.is_match(path) || path.file_name().is_some_and(|name| set.is_match(name))
+}
+
+fn relative<'a>(path: &'a Path, root: &Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+/// Drops `./` components so that `./docs/a.md` matches `docs/*.md`.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create(root: &Path, files: &[&str]) {
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "# Title\n").unwrap();
+        }
+    }
+
+    fn discover(root: &Path, config: &FilesConfig) -> Vec<String> {
+        let mut files = Vec::new();
+        FileDiscovery::new(config)
+            .unwrap()
+            .discover(&[root.to_path_buf()], |path| {
+                let relative = path.strip_prefix(root).unwrap();
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            });
+        files
+    }
+
+    #[test]
+    fn test_ignore_files_and_hidden_directories() {
+        let dir = TempDir::new().unwrap();
+        create(
+            dir.path(),
+            &[
+                "README.md",
+                "docs/guide.md",
+                "node_modules/pkg/README.md",
+                "target/doc.md",
+                ".github/PULL_REQUEST_TEMPLATE.md",
+            ],
+        );
+        fs::write(dir.path().join(".gitignore"), "node_modules/\n").unwrap();
+        fs::write(dir.path().join(".ignore"), "target\n").unwrap();
+
+        assert_eq!(
+            discover(dir.path(), &FilesConfig::default()),
+            vec!["README.md", "docs/guide.md"]
+        );
+
+        let everything = FilesConfig {
+            respect_ignore_files: false,
+            hidden: true,
+            ..FilesConfig::default()
+        };
+        assert_eq!(discover(dir.path(), &everything).len(), 5);
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() {
+        let dir = TempDir::new().unwrap();
+        create(
+            dir.path(),
+            &[
+                "README.md",
+                "CHANGELOG.md",
+                "docs/guide.md",
+                "docs/CHANGELOG.md",
+                "docs/generated/api.md",
+                "vendor/lib/README.md",
+            ],
+        );
+
+        let config = FilesConfig {
+            exclude: vec![
+                "CHANGELOG.md".to_string(),
+                "docs/generated".to_string(),
+                "vendor/**".to_string(),
+            ],
+            ..FilesConfig::default()
+        };
+        assert_eq!(
+            discover(dir.path(), &config),
+            vec!["README.md", "docs/guide.md"]
+        );
+
+        let config = FilesConfig {
+            include: vec!["docs/*.md".to_string()],
+            ..FilesConfig::default()
+        };
+        assert_eq!(
+            discover(dir.path(), &config),
+            vec!["docs/CHANGELOG.md", "docs/guide.md"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_extensions() {
+        let dir = TempDir::new().unwrap();
+        create(
+            dir.path(),
+            &[
+                "README",
+                "docs/a.markdown",
+                "docs/b.MD",
+                "docs/c.mdown",
+                "docs/d.mkd",
+                "docs/notes.txt",
+                "LICENSE",
+            ],
+        );
+
+        assert_eq!(
+            discover(dir.path(), &FilesConfig::default()),
+            vec![
+                "README",
+                "docs/a.markdown",
+                "docs/b.MD",
+                "docs/c.mdown",
+                "docs/d.mkd"
+            ]
+        );
+
+        let config = FilesConfig {
+            extensions: vec![".txt".to_string()],
+            file_names: Vec::new(),
+            ..FilesConfig::default()
+        };
+        assert_eq!(discover(dir.path(), &config), vec!["docs/notes.txt"]);
+    }
+
+    #[test]
+    fn test_explicit_files_ignore_extension() {
+        let dir = TempDir::new().unwrap();
+        create(dir.path(), &["notes.txt", "CHANGELOG.txt"]);
+        let config = FilesConfig {
+            exclude: vec!["CHANGELOG.*".to_string()],
+            ..FilesConfig::default()
+        };
+
+        let mut files = Vec::new();
+        FileDiscovery::new(&config).unwrap().discover(
+            &[
+                dir.path().join("notes.txt"),
+                dir.path().join("CHANGELOG.txt"),
+            ],
+            |path| files.push(path),
+        );
+
+        assert_eq!(files, vec![dir.path().join("notes.txt")]);
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        let config = FilesConfig {
+            exclude: vec!["docs/[".to_string()],
+            ..FilesConfig::default()
+        };
+        assert!(matches!(
+            FileDiscovery::new(&config),
+            Err(DiscoveryError::Glob { pattern, .. }) if pattern == "docs/["
+        ));
+    }
+}
@@ -1,3 +1,13 @@
+pub mod baseline;
+pub mod cache;
+pub mod config;
+pub mod diff;
+pub mod discovery;
 pub mod link_checker;
 pub mod linter;
+pub mod markdownlint;
+pub mod reporter;
 pub mod rules;
+pub mod schemes;
+pub mod suppression;
+pub mod text;
@@ -1,55 +1,102 @@
 use colored::*;
-use futures::stream::{self, StreamExt};
-use pulldown_cmark::{Event, Parser, Tag};
-use reqwest::{Client, StatusCode, redirect::Policy};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+use percent_encoding::percent_decode_str;
+use pulldown_cmark::{Event, LinkType, Parser, Tag, TagEnd};
+use regex::Regex;
+use reqwest::{Certificate, Client, ClientBuilder, Proxy, StatusCode, redirect::Policy};
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
+use thiserror::Error;
 use url::Url;
 
+use crate::cache::LinkCache;
+use crate::config::{Config, FilesConfig, LinksConfig};
+use crate::reporter::{ConsoleReporter, NoopReporter, Progress, Reporter, Summary};
+use crate::schemes::{self, DEFAULT_ALLOWED_SCHEMES};
+use crate::text::LineIndex;
+
 #[derive(Debug, Clone)]
 pub struct LinkInfo {
     pub url: String,
     pub file_path: PathBuf,
+    /// 1-based line of the link in `file_path`.
+    pub line: usize,
+}
+
+/// Where the status of a [`CheckResult`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultOrigin {
+    /// The URL was requested over the network.
+    Network,
+    /// The result was read from the persistent link cache.
+    Cache,
+    /// A local file, anchor or URL syntax check that needs no network.
+    Local,
+    /// A remote link that could not be checked (offline without a cached result).
+    Unchecked,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CheckResult {
     pub link: LinkInfo,
     pub status: StatusCode,
     pub error_message: Option<String>,
+    pub origin: ResultOrigin,
+}
+
+impl CheckResult {
+    /// Returns true if the link is known to be broken. Unchecked links are
+    /// never failures.
+    pub fn is_failure(&self) -> bool {
+        self.origin != ResultOrigin::Unchecked && !self.status.is_success()
+    }
 }
 
-/// Extracts all valid HTTP and HTTPS links from the given Markdown content.
+/// Extracts all valid HTTP and HTTPS links from the given Markdown content
+/// whose scheme `options` allows. The others are reported by
+/// [`check_local_links`] instead of being requested.
 ///
 /// # Examples
 ///
 /// ```
 /// use std::path::Path;
-/// use md_check::link_checker::extract_links;
+/// use md_check::link_checker::{LocalCheckOptions, extract_links};
 ///
 /// let content = "Check out [Rust](https://www.rust-lang.org) and [GitHub](https://github.com).";
 /// let file_path = Path::new("example.md");
 ///
-/// let links = extract_links(content, file_path);
+/// let links = extract_links(content, file_path, &LocalCheckOptions::default());
 ///
 /// assert_eq!(links.len(), 2);
 /// assert_eq!(links[0].url, "https://www.rust-lang.org");
 /// assert_eq!(links[1].url, "https://github.com");
 /// ```
-pub fn extract_links(content: &str, file_path: &Path) -> Vec<LinkInfo> {
-    let parser = Parser::new(content);
+pub fn extract_links(
+    content: &str,
+    file_path: &Path,
+    options: &LocalCheckOptions,
+) -> Vec<LinkInfo> {
+    let parser = Parser::new(content).into_offset_iter();
+    let line_index = LineIndex::new(content);
     let mut links = Vec::new();
 
-    for event in parser {
+    for (event, range) in parser {
         if let Event::Start(Tag::Link { dest_url, .. }) = event {
             let url_str = dest_url.to_string();
-            if (url_str.starts_with("http://") || url_str.starts_with("https://"))
-                && Url::parse(&url_str).is_ok()
-            {
+            let allowed = Url::parse(&url_str).is_ok_and(|url| {
+                schemes::validate_scheme_link(url.scheme(), &url_str, &options.allowed_schemes)
+                    .is_ok()
+            });
+            if (url_str.starts_with("http://") || url_str.starts_with("https://")) && allowed {
                 links.push(LinkInfo {
                     url: url_str,
                     file_path: file_path.to_path_buf(),
+                    line: line_index.line(range.start),
                 });
             }
         }
@@ -57,189 +104,839 @@ pub fn extract_links(content: &str, file_path: &Path) -> Vec<LinkInfo> {
     links
 }
 
-static COUNTER: AtomicUsize = AtomicUsize::new(0);
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("invalid proxy URL {url}: {source}")]
+    Proxy { url: String, source: reqwest::Error },
+    #[error("failed to read CA bundle {path}: {source}")]
+    CaBundleRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid CA bundle {path}: {source}")]
+    CaBundleParse {
+        path: PathBuf,
+        source: reqwest::Error,
+    },
+    #[error("failed to build HTTP client: {0}")]
+    Build(#[from] reqwest::Error),
+}
+
+/// Network settings for the HTTP client used by [`LinkChecker`].
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// Total time allowed for a single request, including redirects.
+    pub timeout: Duration,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub user_agent: String,
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    /// Hosts that bypass the configured proxies, in `NO_PROXY` syntax.
+    pub no_proxy: Vec<String>,
+    /// PEM bundle with additional trusted root certificates.
+    pub ca_bundle: Option<PathBuf>,
+    /// Hosts whose TLS certificates are not verified. A leading `*.` matches
+    /// any subdomain.
+    pub insecure_hosts: Vec<String>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            connect_timeout: None,
+            read_timeout: None,
+            user_agent: format!("markdown-link-checker/{}", env!("CARGO_PKG_VERSION")),
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: Vec::new(),
+            ca_bundle: None,
+            insecure_hosts: Vec::new(),
+        }
+    }
+}
+
+impl ClientOptions {
+    /// Builds client options from the `[links]` config section, keeping the
+    /// defaults for anything that is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use md_check::config::Config;
+    /// use md_check::link_checker::ClientOptions;
+    ///
+    /// let config = Config::parse("[links]\ntimeout = 5\nuser_agent = \"docs-bot\"\n").unwrap();
+    /// let options = ClientOptions::from_config(&config.links);
+    ///
+    /// assert_eq!(options.timeout, Duration::from_secs(5));
+    /// assert_eq!(options.user_agent, "docs-bot");
+    /// ```
+    pub fn from_config(config: &LinksConfig) -> Self {
+        let defaults = Self::default();
+        Self {
+            timeout: config
+                .timeout
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            connect_timeout: config.connect_timeout.map(Duration::from_secs),
+            read_timeout: config.read_timeout.map(Duration::from_secs),
+            user_agent: config.user_agent.clone().unwrap_or(defaults.user_agent),
+            http_proxy: config.http_proxy.clone(),
+            https_proxy: config.https_proxy.clone(),
+            no_proxy: config.no_proxy.clone(),
+            ca_bundle: config.ca_bundle.clone(),
+            insecure_hosts: config.insecure_hosts.clone(),
+        }
+    }
 
-/// Asynchronously checks a list of extracted links by making HTTP requests.
+    fn is_insecure_host(&self, host: &str) -> bool {
+        // Host names are case-insensitive
+        let host = host.to_ascii_lowercase();
+        self.insecure_hosts.iter().any(|pattern| {
+            let pattern = pattern.to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|prefix| prefix.ends_with('.')),
+                None => pattern == host,
+            }
+        })
+    }
+
+    fn builder(&self) -> Result<ClientBuilder, ClientError> {
+        let mut builder = Client::builder()
+            .redirect(Policy::limited(10))
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent);
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(read_timeout) = self.read_timeout {
+            builder = builder.read_timeout(read_timeout);
+        }
+
+        let no_proxy = reqwest::NoProxy::from_string(&self.no_proxy.join(","));
+        if let Some(url) = &self.http_proxy {
+            let proxy = Proxy::http(url).map_err(|source| ClientError::Proxy {
+                url: url.clone(),
+                source,
+            })?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+        }
+        if let Some(url) = &self.https_proxy {
+            let proxy = Proxy::https(url).map_err(|source| ClientError::Proxy {
+                url: url.clone(),
+                source,
+            })?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy));
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let pem = fs::read(path).map_err(|source| ClientError::CaBundleRead {
+                path: path.clone(),
+                source,
+            })?;
+            let certs = Certificate::from_pem_bundle(&pem).map_err(|source| {
+                ClientError::CaBundleParse {
+                    path: path.clone(),
+                    source,
+                }
+            })?;
+            builder = builder.tls_certs_merge(certs);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// The default client plus, when an insecure allowlist is configured, a second
+/// client that skips certificate verification for those hosts only.
+struct HttpClients {
+    options: ClientOptions,
+    default: Client,
+    insecure: Option<Client>,
+}
+
+impl HttpClients {
+    fn new(options: &ClientOptions) -> Result<Self, ClientError> {
+        let default = options.builder()?.build()?;
+        let insecure = if options.insecure_hosts.is_empty() {
+            None
+        } else {
+            Some(
+                options
+                    .builder()?
+                    .tls_danger_accept_invalid_certs(true)
+                    .build()?,
+            )
+        };
+
+        Ok(Self {
+            options: options.clone(),
+            default,
+            insecure,
+        })
+    }
+
+    fn for_url(&self, url: &str) -> &Client {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned));
+
+        match (&self.insecure, host) {
+            (Some(insecure), Some(host)) if self.options.is_insecure_host(&host) => insecure,
+            _ => &self.default,
+        }
+    }
+}
+
+static HTML_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:id|name)\s*=\s*["']([^"']+)["']"#).expect("valid regex")
+});
+
+/// Settings for the checks in [`check_local_links`].
+#[derive(Debug, Clone)]
+pub struct LocalCheckOptions {
+    /// URL schemes that links may use. Anything else is reported, with a
+    /// suggestion when the scheme looks like a typo of an allowed one.
+    pub allowed_schemes: Vec<String>,
+    /// Extensions of Markdown files, whose headings are the anchors that
+    /// `file#anchor` links are checked against.
+    pub markdown_extensions: Vec<String>,
+}
+
+impl Default for LocalCheckOptions {
+    fn default() -> Self {
+        Self {
+            allowed_schemes: DEFAULT_ALLOWED_SCHEMES
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            markdown_extensions: FilesConfig::default().extensions,
+        }
+    }
+}
+
+impl LocalCheckOptions {
+    /// Builds local check options from the `[links]` and `[files]` config
+    /// sections.
+    pub fn from_config(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            allowed_schemes: match &config.links.allowed_schemes {
+                Some(schemes) => schemes.iter().map(|s| s.to_ascii_lowercase()).collect(),
+                None => defaults.allowed_schemes,
+            },
+            markdown_extensions: config.files.extensions.clone(),
+        }
+    }
+
+    fn is_markdown(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            self.markdown_extensions
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known.as_str()))
+        })
+    }
+}
+
+/// Validates every link that can be checked without the network: relative
+/// file links, `#anchor` fragments, the syntax of HTTP(S) URLs, `mailto:`
+/// and `tel:` links, and whether the URL scheme is allowed.
 ///
-/// This function uses a concurrent stream to verify the status of each URL.
+/// Returns one result per checked link; broken links have a non-success status.
 ///
 /// # Examples
 ///
-/// ```no_run
-/// # #[tokio::main]
-/// # async fn main() {
-/// use std::path::PathBuf;
-/// use md_check::link_checker::{check_links, LinkInfo};
-///
-/// let links = vec![
-///     LinkInfo {
-///         url: "[https://www.rust-lang.org](https://www.rust-lang.org)".to_string(),
-///         file_path: PathBuf::from("test.md"),
-///     }
-/// ];
+/// ```
+/// use std::path::Path;
+/// use md_check::link_checker::{check_local_links, LocalCheckOptions};
 ///
-/// // This will perform actual network requests
-/// let results = check_links(links).await;
+/// let content = "# Setup\n\nSee [setup](#setup), [install](#install), \
+///                [bad](https://example.com:99999) and [mail](mailto:help@example).";
+/// let results = check_local_links(content, Path::new("guide.md"), &LocalCheckOptions::default());
 ///
-/// assert_eq!(results.len(), 1);
-/// assert!(results[0].status.is_success());
-/// # }
+/// let broken: Vec<_> = results.iter().filter(|r| r.is_failure()).collect();
+/// assert_eq!(broken.len(), 3);
+/// assert_eq!(broken[0].link.url, "#install");
 /// ```
-pub async fn check_links(links: Vec<LinkInfo>) -> Vec<CheckResult> {
-    let is_github_actions = std::env::var("GITHUB_ACTIONS").is_ok();
-
-    let client = Client::builder()
-        .redirect(Policy::limited(10))
-        .timeout(Duration::from_secs(30))
-        .user_agent(format!(
-            "markdown-link-checker/{}",
-            env!("CARGO_PKG_VERSION")
-        ))
-        .build()
-        .unwrap_or_default();
-
-    let total_links = links.len();
-
-    if is_github_actions {
-        println!("::notice::Found {} links to check", total_links);
-        for link in &links {
-            println!(
-                "::debug::Checking link: {} in {}",
-                link.url,
-                link.file_path.display()
-            );
-        }
+pub fn check_local_links(
+    content: &str,
+    file_path: &Path,
+    options: &LocalCheckOptions,
+) -> Vec<CheckResult> {
+    let mut anchors_by_file: HashMap<PathBuf, Option<HashSet<String>>> = HashMap::new();
+    anchors_by_file.insert(file_path.to_path_buf(), Some(collect_anchors(content)));
+
+    let line_index = LineIndex::new(content);
+    let mut results = Vec::new();
+    for (event, range) in Parser::new(content).into_offset_iter() {
+        let Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            ..
+        }) = event
+        else {
+            continue;
+        };
+        let url = dest_url.to_string();
+
+        let error = if link_type == LinkType::Email {
+            // Autolinks like <support@example.com> carry no scheme
+            schemes::validate_email(&url)
+                .err()
+                .map(|e| (StatusCode::BAD_REQUEST, e))
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            // Allowed remote links are left to the network check
+            match Url::parse(&url) {
+                Ok(parsed) => match schemes::validate_scheme_link(
+                    parsed.scheme(),
+                    &url,
+                    &options.allowed_schemes,
+                ) {
+                    Ok(()) => continue,
+                    Err(e) => Some((StatusCode::BAD_REQUEST, e)),
+                },
+                Err(e) => Some((StatusCode::BAD_REQUEST, format!("Invalid URL: {}", e))),
+            }
+        } else if url.is_empty() || url.starts_with("//") || url.starts_with('/') {
+            // Empty links are reported by the linter; root-relative links
+            // depend on where the documentation is served from.
+            continue;
+        } else if let Ok(parsed) = Url::parse(&url) {
+            schemes::validate_scheme_link(parsed.scheme(), &url, &options.allowed_schemes)
+                .err()
+                .map(|e| (StatusCode::BAD_REQUEST, e))
+        } else {
+            check_local_target(&url, file_path, options, &mut anchors_by_file)
+                .map(|e| (StatusCode::NOT_FOUND, e))
+        };
+
+        let (status, error_message) = match error {
+            Some((status, message)) => (status, Some(message)),
+            None => (StatusCode::OK, None),
+        };
+        results.push(CheckResult {
+            link: LinkInfo {
+                url,
+                file_path: file_path.to_path_buf(),
+                line: line_index.line(range.start),
+            },
+            status,
+            error_message,
+            origin: ResultOrigin::Local,
+        });
     }
+    results
+}
 
-    println!("\n{} {} links to check", "Total:".bold(), total_links);
+fn check_local_target(
+    url: &str,
+    file_path: &Path,
+    options: &LocalCheckOptions,
+    anchors_by_file: &mut HashMap<PathBuf, Option<HashSet<String>>>,
+) -> Option<String> {
+    let (path_part, anchor) = match url.split_once('#') {
+        Some((path, anchor)) => (path, Some(anchor)),
+        None => (url, None),
+    };
+    let path_part = path_part.split('?').next().unwrap_or_default();
 
-    COUNTER.store(0, Ordering::SeqCst);
+    let target = if path_part.is_empty() {
+        file_path.to_path_buf()
+    } else {
+        let decoded = percent_decode_str(path_part).decode_utf8_lossy();
+        file_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(decoded.as_ref())
+    };
 
-    let results = stream::iter(links)
-        .map(|link| {
-            let client = client.clone();
-            async move {
-                let result = check_single_link(&client, link.clone()).await;
-                let current = COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+    if !path_part.is_empty() && !target.exists() {
+        return Some(format!("File not found: {}", target.display()));
+    }
 
-                let status_str = match result.status.as_u16() {
-                    200..=299 => result.status.to_string().green(),
-                    300..=399 => result.status.to_string().yellow(),
-                    400..=499 => result.status.to_string().red(),
-                    _ => result.status.to_string().red().bold(),
-                };
+    let anchor = anchor.filter(|a| !a.is_empty())?;
+    if !options.is_markdown(&target) {
+        return None;
+    }
+
+    let anchors = anchors_by_file.entry(target.clone()).or_insert_with(|| {
+        fs::read_to_string(&target)
+            .ok()
+            .map(|content| collect_anchors(&content))
+    });
+    let anchor = percent_decode_str(anchor)
+        .decode_utf8_lossy()
+        .to_lowercase();
+
+    match anchors {
+        Some(anchors) if !anchors.contains(&anchor) => Some(format!(
+            "Anchor #{} not found in {}",
+            anchor,
+            target.display()
+        )),
+        _ => None,
+    }
+}
+
+/// Collects the anchors GitHub generates for headings, plus explicit
+/// `id`/`name` attributes in raw HTML.
+fn collect_anchors(content: &str) -> HashSet<String> {
+    let mut anchors = HashSet::new();
+    let mut slug_counts: HashMap<String, usize> = HashMap::new();
+    let mut heading_text: Option<String> = None;
 
-                if is_github_actions {
-                    if result.status.is_success() {
-                        println!(
-                            "::debug::Link {} status: {} (success)",
-                            link.url, result.status
-                        );
+    for event in Parser::new(content) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => heading_text = Some(String::new()),
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(text) = heading_text.take() {
+                    let slug = slugify(&text);
+                    let count = slug_counts.entry(slug.clone()).or_insert(0);
+                    let anchor = if *count == 0 {
+                        slug.clone()
                     } else {
-                        println!(
-                            "::error file={}::Link {} failed with status {}{}",
-                            link.file_path.display(),
-                            link.url,
-                            result.status,
-                            result
-                                .error_message
-                                .as_ref()
-                                .map(|m| format!(" - {}", m))
-                                .unwrap_or_default()
-                        );
-                    }
-                } else {
-                    println!(
-                        "[{}/{}] {} - {} - {}",
-                        current,
-                        total_links,
-                        status_str,
-                        if result.status.is_success() {
-                            "GOOD".green()
-                        } else {
-                            "FAIL".red()
-                        },
-                        link.url
-                    );
+                        format!("{}-{}", slug, count)
+                    };
+                    *count += 1;
+                    anchors.insert(anchor);
                 }
-                result
             }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = heading_text.as_mut() {
+                    heading.push_str(&text);
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for capture in HTML_ID.captures_iter(&html) {
+                    anchors.insert(capture[1].to_lowercase());
+                }
+            }
+            _ => {}
+        }
+    }
+    anchors
+}
+
+/// Converts heading text into a GitHub-style anchor slug.
+fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            c if c.is_whitespace() => Some('-'),
+            _ => None,
         })
-        .buffer_unordered(10)
-        .collect::<Vec<_>>()
-        .await;
+        .collect()
+}
 
-    if is_github_actions {
-        println!("::endgroup::");
+/// Checks remote links, reporting progress through a [`Reporter`].
+///
+/// All state lives in the instance, so several checkers can run at the same
+/// time. Build one with [`LinkChecker::builder`].
+pub struct LinkChecker {
+    clients: Option<HttpClients>,
+    reporter: Arc<dyn Reporter>,
+    concurrency: usize,
+    max_attempts: u32,
+    retry_delay: Duration,
+    cache: Option<LinkCache>,
+}
+
+/// Builder for [`LinkChecker`].
+pub struct LinkCheckerBuilder {
+    options: ClientOptions,
+    reporter: Arc<dyn Reporter>,
+    concurrency: usize,
+    max_attempts: u32,
+    retry_delay: Duration,
+    offline: bool,
+    cache: Option<LinkCache>,
+}
+
+impl Default for LinkCheckerBuilder {
+    fn default() -> Self {
+        Self {
+            options: ClientOptions::default(),
+            reporter: Arc::new(NoopReporter),
+            concurrency: 10,
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(1),
+            offline: false,
+            cache: None,
+        }
     }
+}
 
-    // Print summary
-    let successful = results.iter().filter(|r| r.status.is_success()).count();
-    let redirects = results.iter().filter(|r| r.status.is_redirection()).count();
-    let failed = results
-        .iter()
-        .filter(|r| r.status.is_client_error() || r.status.is_server_error())
-        .count();
+impl LinkCheckerBuilder {
+    pub fn client_options(mut self, options: ClientOptions) -> Self {
+        self.options = options;
+        self
+    }
 
-    if is_github_actions {
-        println!("::group::Summary");
+    /// Sets the reporter that receives progress events. Defaults to
+    /// [`NoopReporter`].
+    pub fn reporter(mut self, reporter: impl Reporter + 'static) -> Self {
+        self.reporter = Arc::new(reporter);
+        self
     }
 
-    println!("\n{}", "Link check completed.".bold());
-    println!("\n{}", "Summary:".bold());
-    println!("{}: {}", "Successful".green(), successful);
-    if redirects > 0 {
-        println!("{}: {}", "Redirects".yellow(), redirects);
+    /// Maximum number of requests in flight. Defaults to 10.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
     }
-    if failed > 0 {
-        println!("{}: {}", "Failed".red(), failed);
+
+    /// Number of attempts per link before it is reported as failed, and the
+    /// pause between them. Defaults to 3 attempts one second apart.
+    pub fn retries(mut self, max_attempts: u32, delay: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.retry_delay = delay;
+        self
     }
 
-    if is_github_actions {
-        println!("::endgroup::");
+    /// Resolves remote links without touching the network. Links with an
+    /// entry in the [`cache`](Self::cache) reuse the stored status; all others
+    /// are reported as [`ResultOrigin::Unchecked`].
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
     }
 
-    results
+    /// Link cache consulted in offline mode.
+    pub fn cache(mut self, cache: LinkCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Builds the checker. Fails if the client options cannot be turned into
+    /// an HTTP client, for example because a proxy URL is malformed or the CA
+    /// bundle is unreadable. No client is built in offline mode.
+    pub fn build(self) -> Result<LinkChecker, ClientError> {
+        let clients = if self.offline {
+            None
+        } else {
+            Some(HttpClients::new(&self.options)?)
+        };
+
+        Ok(LinkChecker {
+            clients,
+            reporter: self.reporter,
+            concurrency: self.concurrency,
+            max_attempts: self.max_attempts,
+            retry_delay: self.retry_delay,
+            cache: self.cache,
+        })
+    }
 }
 
-async fn check_single_link(client: &Client, link: LinkInfo) -> CheckResult {
-    let mut retries = 3;
-    let initial_url = link.url.clone();
-
-    loop {
-        match client.get(&link.url).send().await {
-            Ok(response) => {
-                return CheckResult {
-                    link: LinkInfo {
-                        url: initial_url,
-                        file_path: link.file_path,
-                    },
-                    status: response.status(),
-                    error_message: if response.status().is_success() {
-                        None
-                    } else {
-                        Some(format!("HTTP {}", response.status()))
-                    },
+impl LinkChecker {
+    /// Starts building a checker with default client options and no reporter.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::path::PathBuf;
+    /// use std::time::Duration;
+    /// use md_check::link_checker::{ClientOptions, LinkChecker, LinkInfo};
+    /// use md_check::reporter::ConsoleReporter;
+    ///
+    /// let checker = LinkChecker::builder()
+    ///     .client_options(ClientOptions {
+    ///         timeout: Duration::from_secs(10),
+    ///         ..ClientOptions::default()
+    ///     })
+    ///     .reporter(ConsoleReporter::new())
+    ///     .build()
+    ///     .unwrap();
+    /// let links = vec![LinkInfo {
+    ///     url: "https://www.rust-lang.org".to_string(),
+    ///     file_path: PathBuf::from("test.md"),
+    ///     line: 1,
+    /// }];
+    ///
+    /// let results = checker.check(links).await;
+    ///
+    /// assert_eq!(results.len(), 1);
+    /// # }
+    /// ```
+    pub fn builder() -> LinkCheckerBuilder {
+        LinkCheckerBuilder::default()
+    }
+
+    /// Checks the given links and returns one result per link, sorted by file,
+    /// line and URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::path::PathBuf;
+    /// use md_check::link_checker::{LinkChecker, LinkInfo, ResultOrigin};
+    ///
+    /// let checker = LinkChecker::builder().offline(true).build().unwrap();
+    /// let links = vec![LinkInfo {
+    ///     url: "https://www.rust-lang.org".to_string(),
+    ///     file_path: PathBuf::from("test.md"),
+    ///     line: 1,
+    /// }];
+    ///
+    /// let results = checker.check(links).await;
+    ///
+    /// assert_eq!(results[0].origin, ResultOrigin::Unchecked);
+    /// assert!(!results[0].is_failure());
+    /// # }
+    /// ```
+    pub async fn check(&self, links: Vec<LinkInfo>) -> Vec<CheckResult> {
+        let total = links.len();
+        self.run(stream::iter(links), Some(total)).await
+    }
+
+    /// Checks links as they arrive from `links`, so requests can start while
+    /// the producer is still extracting them.
+    ///
+    /// The total passed to the reporter is only known once the end of the
+    /// stream has been seen. Results are sorted by file, line and URL, independent of the
+    /// order in which requests complete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use std::path::PathBuf;
+    /// use futures::stream;
+    /// use md_check::link_checker::{LinkChecker, LinkInfo};
+    ///
+    /// let checker = LinkChecker::builder().offline(true).build().unwrap();
+    /// let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    ///
+    /// tokio::spawn(async move {
+    ///     for line in [2, 1] {
+    ///         let link = LinkInfo {
+    ///             url: "https://www.rust-lang.org".to_string(),
+    ///             file_path: PathBuf::from("test.md"),
+    ///             line,
+    ///         };
+    ///         tx.send(link).await.unwrap();
+    ///     }
+    /// });
+    ///
+    /// let links = stream::poll_fn(move |cx| rx.poll_recv(cx));
+    /// let results = checker.check_stream(links).await;
+    ///
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(results[0].link.line, 1);
+    /// # }
+    /// ```
+    pub async fn check_stream(&self, links: impl Stream<Item = LinkInfo>) -> Vec<CheckResult> {
+        self.run(links, None).await
+    }
+
+    async fn run(
+        &self,
+        links: impl Stream<Item = LinkInfo>,
+        known_total: Option<usize>,
+    ) -> Vec<CheckResult> {
+        let queued = AtomicUsize::new(0);
+        let input_done = AtomicBool::new(false);
+        let completed = AtomicUsize::new(0);
+
+        // A trailing `None` marks the end of the input, which fixes the total
+        let links = links
+            .map(Some)
+            .chain(stream::once(async { None }))
+            .filter_map(|link| {
+                match &link {
+                    Some(link) => {
+                        queued.fetch_add(1, Ordering::SeqCst);
+                        self.reporter.started(link);
+                    }
+                    None => input_done.store(true, Ordering::SeqCst),
+                }
+                future::ready(link)
+            });
+
+        let mut results: Vec<CheckResult> = links
+            .map(|link| async move {
+                match &self.clients {
+                    Some(clients) => {
+                        let client = clients.for_url(&link.url).clone();
+                        self.check_single_link(&client, link).await
+                    }
+                    None => self.check_offline(link),
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .map(|result| {
+                let progress = Progress {
+                    completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                    total: known_total.or_else(|| {
+                        input_done
+                            .load(Ordering::SeqCst)
+                            .then(|| queued.load(Ordering::SeqCst))
+                    }),
                 };
-            }
-            Err(e) => {
-                retries -= 1;
-                if retries == 0 {
+                self.reporter.finished(&result, progress);
+                result
+            })
+            .collect()
+            .await;
+
+        results.sort_by(|a, b| {
+            (&a.link.file_path, a.link.line, &a.link.url).cmp(&(
+                &b.link.file_path,
+                b.link.line,
+                &b.link.url,
+            ))
+        });
+        self.reporter.summary(&Summary::from_results(&results));
+        results
+    }
+
+    /// Finds successful `http://` links whose `https://` variant also works.
+    ///
+    /// Returns each such link together with its HTTPS URL. Every distinct URL
+    /// is probed once, without retries; probe failures are not reported.
+    /// Offline checkers never find upgrades.
+    pub async fn find_https_upgrades(&self, results: &[CheckResult]) -> Vec<(LinkInfo, String)> {
+        let Some(clients) = &self.clients else {
+            return Vec::new();
+        };
+
+        let candidates: HashSet<&str> = results
+            .iter()
+            .filter(|r| r.origin == ResultOrigin::Network && r.status.is_success())
+            .filter_map(|r| r.link.url.strip_prefix("http://"))
+            .collect();
+
+        let available: HashSet<String> = stream::iter(candidates)
+            .map(|rest| {
+                let https_url = format!("https://{}", rest);
+                let client = clients.for_url(&https_url).clone();
+                async move {
+                    match client.get(&https_url).send().await {
+                        Ok(response) if response.status().is_success() => Some(https_url),
+                        _ => None,
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .filter_map(|url| async move { url })
+            .collect()
+            .await;
+
+        results
+            .iter()
+            .filter_map(|r| {
+                let https_url = format!("https://{}", r.link.url.strip_prefix("http://")?);
+                available
+                    .contains(&https_url)
+                    .then(|| (r.link.clone(), https_url))
+            })
+            .collect()
+    }
+
+    fn check_offline(&self, link: LinkInfo) -> CheckResult {
+        match self.cache.as_ref().and_then(|c| c.get(&link.url)) {
+            Some(cached) => CheckResult {
+                link,
+                status: StatusCode::from_u16(cached.status)
+                    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                error_message: cached.error_message.clone(),
+                origin: ResultOrigin::Cache,
+            },
+            None => CheckResult {
+                link,
+                status: StatusCode::OK,
+                error_message: Some("Not checked in offline mode".to_string()),
+                origin: ResultOrigin::Unchecked,
+            },
+        }
+    }
+
+    async fn check_single_link(&self, client: &Client, link: LinkInfo) -> CheckResult {
+        let mut attempt = 1;
+
+        loop {
+            match client.get(&link.url).send().await {
+                Ok(response) => {
                     return CheckResult {
-                        link: LinkInfo {
-                            url: initial_url,
-                            file_path: link.file_path,
+                        link,
+                        status: response.status(),
+                        error_message: if response.status().is_success() {
+                            None
+                        } else {
+                            Some(format!("HTTP {}", response.status()))
                         },
-                        status: StatusCode::INTERNAL_SERVER_ERROR,
-                        error_message: Some(format!("Request failed: {}", e)),
+                        origin: ResultOrigin::Network,
                     };
                 }
-                tokio::time::sleep(Duration::from_secs(1)).await;
+                Err(e) => {
+                    if attempt >= self.max_attempts {
+                        return CheckResult {
+                            link,
+                            status: StatusCode::INTERNAL_SERVER_ERROR,
+                            error_message: Some(format!("Request failed: {}", e)),
+                            origin: ResultOrigin::Network,
+                        };
+                    }
+                    self.reporter.retried(&link, attempt, &e.to_string());
+                    attempt += 1;
+                    tokio::time::sleep(self.retry_delay).await;
+                }
             }
         }
     }
 }
 
+/// Asynchronously checks a list of extracted links by making HTTP requests,
+/// printing progress to stdout.
+///
+/// This is a shortcut for a [`LinkChecker`] with default client options and a
+/// [`ConsoleReporter`]. Fails only if the HTTP client cannot be built, for
+/// example because the TLS backend cannot be initialized.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), md_check::link_checker::ClientError> {
+/// use std::path::PathBuf;
+/// use md_check::link_checker::{check_links, LinkInfo};
+///
+/// let links = vec![
+///     LinkInfo {
+///         url: "https://www.rust-lang.org".to_string(),
+///         file_path: PathBuf::from("test.md"),
+///         line: 1,
+///     }
+/// ];
+///
+/// // This will perform actual network requests
+/// let results = check_links(links).await?;
+///
+/// assert_eq!(results.len(), 1);
+/// assert!(results[0].status.is_success());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn check_links(links: Vec<LinkInfo>) -> Result<Vec<CheckResult>, ClientError> {
+    let checker = LinkChecker::builder()
+        .reporter(ConsoleReporter::new())
+        .build()?;
+    Ok(checker.check(links).await)
+}
+
 /// Formats the results of link checks into human-readable error messages.
 ///
 /// It filters out successful requests and returns formatted strings for
@@ -250,16 +947,18 @@ async fn check_single_link(client: &Client, link: LinkInfo) -> CheckResult {
 /// ```
 /// use std::path::PathBuf;
 /// use reqwest::StatusCode;
-/// use md_check::link_checker::{format_check_results, CheckResult, LinkInfo};
+/// use md_check::link_checker::{format_check_results, CheckResult, LinkInfo, ResultOrigin};
 ///
 /// let results = vec![
 ///     CheckResult {
 ///         link: LinkInfo {
 ///             url: "https://invalid.domain.xyz".to_string(),
 ///             file_path: PathBuf::from("doc.md"),
+///             line: 1,
 ///         },
 ///         status: StatusCode::NOT_FOUND,
 ///         error_message: Some("Not Found".to_string()),
+///         origin: ResultOrigin::Network,
 ///     }
 /// ];
 ///
@@ -271,7 +970,7 @@ async fn check_single_link(client: &Client, link: LinkInfo) -> CheckResult {
 pub fn format_check_results(results: &[CheckResult]) -> Vec<String> {
     results
         .iter()
-        .filter(|r| !r.status.is_success())
+        .filter(|r| r.is_failure())
         .map(|r| {
             let status_color = if r.status.is_redirection() {
                 r.status.to_string().yellow()
@@ -280,14 +979,15 @@ pub fn format_check_results(results: &[CheckResult]) -> Vec<String> {
             };
 
             format!(
-                "- {} (Status: {}{}) [in file {}]",
+                "- {} (Status: {}{}) [in file {}:{}]",
                 r.link.url,
                 status_color,
                 r.error_message
                     .as_ref()
                     .map(|msg| format!(" - {}", msg))
                     .unwrap_or_default(),
-                r.link.file_path.display()
+                r.link.file_path.display(),
+                r.link.line
             )
         })
         .collect()
@@ -296,6 +996,55 @@ pub fn format_check_results(results: &[CheckResult]) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
+
+    async fn check_with_options(links: Vec<LinkInfo>, options: ClientOptions) -> Vec<CheckResult> {
+        LinkChecker::builder()
+            .client_options(options)
+            .retries(2, Duration::ZERO)
+            .build()
+            .unwrap()
+            .check(links)
+            .await
+    }
+
+    /// Records every event so tests can assert on them.
+    #[derive(Default)]
+    struct RecordingReporter {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Reporter for RecordingReporter {
+        fn started(&self, link: &LinkInfo) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("started {}", link.line));
+        }
+
+        fn retried(&self, link: &LinkInfo, attempt: u32, _error: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("retried {} {}", link.url, attempt));
+        }
+
+        fn finished(&self, result: &CheckResult, progress: Progress) {
+            self.events.lock().unwrap().push(format!(
+                "finished {} {}/{:?}",
+                result.status.as_u16(),
+                progress.completed,
+                progress.total
+            ));
+        }
+
+        fn summary(&self, summary: &Summary) {
+            self.events.lock().unwrap().push(format!(
+                "summary {} ok, {} failed",
+                summary.successful, summary.failed
+            ));
+        }
+    }
 
     #[tokio::test]
     async fn test_redirect_handling() {
@@ -320,9 +1069,10 @@ mod tests {
         let link_info = LinkInfo {
             url: format!("{}/redirect", server_url),
             file_path: PathBuf::from("test.md"),
+            line: 1,
         };
 
-        let results = check_links(vec![link_info]).await;
+        let results = check_links(vec![link_info]).await.unwrap();
 
         assert_eq!(results.len(), 1);
         let result = &results[0];
@@ -359,9 +1109,10 @@ mod tests {
         let link_info = LinkInfo {
             url: format!("{}/redirect-error", server_url),
             file_path: PathBuf::from("test.md"),
+            line: 1,
         };
 
-        let results = check_links(vec![link_info]).await;
+        let results = check_links(vec![link_info]).await.unwrap();
 
         assert_eq!(results.len(), 1);
         let result = &results[0];
@@ -374,4 +1125,354 @@ mod tests {
         mock_redirect.assert_async().await;
         mock_final.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn test_custom_user_agent() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock = server
+            .mock("GET", "/ua")
+            .match_header("user-agent", "docs-bot/1.0")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let options = ClientOptions {
+            user_agent: "docs-bot/1.0".to_string(),
+            ..ClientOptions::default()
+        };
+        let link_info = LinkInfo {
+            url: format!("{}/ua", server.url()),
+            file_path: PathBuf::from("test.md"),
+            line: 1,
+        };
+
+        let results = check_with_options(vec![link_info], options).await;
+
+        assert!(results[0].status.is_success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_proxy() {
+        let mut proxy = mockito::Server::new_async().await;
+
+        // The proxy receives the request for the unreachable host
+        let mock = proxy
+            .mock("GET", "/via-proxy")
+            .match_header("host", "docs.invalid")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let options = ClientOptions {
+            http_proxy: Some(proxy.url()),
+            ..ClientOptions::default()
+        };
+        let link_info = LinkInfo {
+            url: "http://docs.invalid/via-proxy".to_string(),
+            file_path: PathBuf::from("test.md"),
+            line: 1,
+        };
+
+        let results = check_with_options(vec![link_info], options).await;
+
+        assert!(results[0].status.is_success());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_no_proxy_bypasses_proxy() {
+        let mut proxy = mockito::Server::new_async().await;
+        let mut server = mockito::Server::new_async().await;
+
+        let proxy_mock = proxy.mock("GET", "/direct").expect(0).create_async().await;
+        let server_mock = server
+            .mock("GET", "/direct")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let options = ClientOptions {
+            http_proxy: Some(proxy.url()),
+            no_proxy: vec!["127.0.0.1".to_string()],
+            ..ClientOptions::default()
+        };
+        let link_info = LinkInfo {
+            url: format!("{}/direct", server.url()),
+            file_path: PathBuf::from("test.md"),
+            line: 1,
+        };
+
+        let results = check_with_options(vec![link_info], options).await;
+
+        assert!(results[0].status.is_success());
+        proxy_mock.assert_async().await;
+        server_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout() {
+        // Accept connections but never answer them
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                connections.push(socket);
+            }
+        });
+
+        let options = ClientOptions {
+            read_timeout: Some(Duration::from_millis(200)),
+            ..ClientOptions::default()
+        };
+        let link_info = LinkInfo {
+            url: format!("http://{}/slow", addr),
+            file_path: PathBuf::from("test.md"),
+            line: 1,
+        };
+
+        let results = check_with_options(vec![link_info], options).await;
+
+        assert!(!results[0].status.is_success());
+        assert!(
+            results[0]
+                .error_message
+                .as_ref()
+                .is_some_and(|m| m.starts_with("Request failed"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_ca_bundle() {
+        let options = ClientOptions {
+            ca_bundle: Some(PathBuf::from("does-not-exist.pem")),
+            ..ClientOptions::default()
+        };
+
+        let result = LinkChecker::builder().client_options(options).build();
+
+        assert!(matches!(result, Err(ClientError::CaBundleRead { .. })));
+    }
+
+    #[test]
+    fn test_insecure_host_matching() {
+        let options = ClientOptions {
+            insecure_hosts: vec![
+                "self-signed.local".to_string(),
+                "*.internal".to_string(),
+                "*.Internal.Example".to_string(),
+            ],
+            ..ClientOptions::default()
+        };
+
+        assert!(options.is_insecure_host("self-signed.local"));
+        assert!(options.is_insecure_host("docs.internal"));
+        assert!(!options.is_insecure_host("internal"));
+        assert!(!options.is_insecure_host("notinternal"));
+        assert!(!options.is_insecure_host("example.com"));
+        assert!(options.is_insecure_host("Self-Signed.LOCAL"));
+        assert!(options.is_insecure_host("API.Internal.Example"));
+        assert!(options.is_insecure_host("api.internal.example"));
+        assert!(options.is_insecure_host("Docs.INTERNAL"));
+    }
+
+    #[test]
+    fn test_collect_anchors_handles_duplicates_and_html() {
+        let content = "# Getting Started\n\n## Setup\n\n## Setup\n\n<a id=\"Custom-Anchor\"></a>\n";
+
+        let anchors = collect_anchors(content);
+
+        assert!(anchors.contains("getting-started"));
+        assert!(anchors.contains("setup"));
+        assert!(anchors.contains("setup-1"));
+        assert!(anchors.contains("custom-anchor"));
+    }
+
+    #[test]
+    fn test_check_local_links_against_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("index.md");
+        std::fs::write(temp_dir.path().join("other doc.md"), "## Usage `cli`\n").unwrap();
+
+        let content =
+            "[ok](other%20doc.md#usage-cli) [anchor](other%20doc.md#missing) [gone](nope.md)";
+        let results = check_local_links(content, &file_path, &LocalCheckOptions::default());
+
+        assert_eq!(results.len(), 3);
+        assert!(!results[0].is_failure());
+        assert!(
+            results[1]
+                .error_message
+                .as_ref()
+                .is_some_and(|m| m.starts_with("Anchor #missing not found"))
+        );
+        assert!(
+            results[2]
+                .error_message
+                .as_ref()
+                .is_some_and(|m| m.starts_with("File not found"))
+        );
+    }
+
+    #[test]
+    fn test_anchors_in_configured_markdown_extensions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("index.md");
+        std::fs::write(temp_dir.path().join("guide.MDX"), "# Setup\n").unwrap();
+        let content = "[ok](guide.MDX#setup) [anchor](guide.MDX#missing)";
+
+        let mut options = LocalCheckOptions::default();
+        let results = check_local_links(content, &file_path, &options);
+        assert!(results.iter().all(|r| !r.is_failure()));
+
+        options.markdown_extensions.push("mdx".to_string());
+        let results = check_local_links(content, &file_path, &options);
+        assert!(!results[0].is_failure());
+        assert_eq!(
+            results[1].error_message.as_deref(),
+            Some(
+                format!(
+                    "Anchor #missing not found in {}",
+                    temp_dir.path().join("guide.MDX").display()
+                )
+                .as_str()
+            )
+        );
+    }
+
+    #[test]
+    fn test_http_must_be_allowed() {
+        let options = LocalCheckOptions {
+            allowed_schemes: vec!["https".to_string(), "mailto".to_string()],
+            ..LocalCheckOptions::default()
+        };
+        let content = "[plain](http://example.com) [secure](https://example.com)";
+
+        let results = check_local_links(content, Path::new("index.md"), &options);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].link.url, "http://example.com");
+        assert_eq!(
+            results[0].error_message.as_deref(),
+            Some("Unknown URL scheme 'http' (did you mean 'https'?)")
+        );
+
+        let links = extract_links(content, Path::new("index.md"), &options);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].url, "https://example.com");
+    }
+
+    #[tokio::test]
+    async fn test_offline_checker_uses_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut cache = LinkCache::load(temp_dir.path()).unwrap();
+        cache.update(&[CheckResult {
+            link: LinkInfo {
+                url: "https://cached.example".to_string(),
+                file_path: PathBuf::from("test.md"),
+                line: 1,
+            },
+            status: StatusCode::NOT_FOUND,
+            error_message: Some("HTTP 404 Not Found".to_string()),
+            origin: ResultOrigin::Network,
+        }]);
+        cache.save().unwrap();
+
+        let cache = LinkCache::load(temp_dir.path()).unwrap();
+        let links = ["https://cached.example", "https://new.example"]
+            .into_iter()
+            .map(|url| LinkInfo {
+                url: url.to_string(),
+                file_path: PathBuf::from("test.md"),
+                line: 1,
+            })
+            .collect();
+
+        let checker = LinkChecker::builder()
+            .offline(true)
+            .cache(cache)
+            .build()
+            .unwrap();
+        let results = checker.check(links).await;
+
+        assert_eq!(results[0].origin, ResultOrigin::Cache);
+        assert_eq!(results[0].status, StatusCode::NOT_FOUND);
+        assert!(results[0].is_failure());
+        assert_eq!(results[1].origin, ResultOrigin::Unchecked);
+        assert!(!results[1].is_failure());
+    }
+
+    #[tokio::test]
+    async fn test_find_https_upgrades_skips_unavailable() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/plain")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let link = LinkInfo {
+            url: format!("{}/plain", server.url()),
+            file_path: PathBuf::from("test.md"),
+            line: 1,
+        };
+        let checker = LinkChecker::builder().build().unwrap();
+        let results = checker.check(vec![link]).await;
+
+        // The mock server does not speak TLS, so no upgrade is suggested
+        let upgrades = checker.find_https_upgrades(&results).await;
+
+        assert!(upgrades.is_empty());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_reporter_receives_events() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ok")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        // Nothing listens on port 9 of the loopback interface
+        let links = vec![
+            LinkInfo {
+                url: format!("{}/ok", server.url()),
+                file_path: PathBuf::from("test.md"),
+                line: 1,
+            },
+            LinkInfo {
+                url: "http://127.0.0.1:9/down".to_string(),
+                file_path: PathBuf::from("test.md"),
+                line: 2,
+            },
+        ];
+
+        let reporter = RecordingReporter::default();
+        let events = Arc::clone(&reporter.events);
+        let checker = LinkChecker::builder()
+            .reporter(reporter)
+            .concurrency(1)
+            .retries(2, Duration::ZERO)
+            .build()
+            .unwrap();
+
+        checker.check(links).await;
+
+        let events = events.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                "started 1",
+                "finished 200 1/Some(2)",
+                "started 2",
+                "retried http://127.0.0.1:9/down 1",
+                "finished 500 2/Some(2)",
+                "summary 1 ok, 1 failed",
+            ]
+        );
+        mock.assert_async().await;
+    }
 }
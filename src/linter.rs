@@ -1,9 +1,17 @@
-use pulldown_cmark::{Event, Parser, Tag};
+use pulldown_cmark::{Event, Parser};
 use std::path::Path;
 
 // setup module system rules
-use crate::rules::common::{LintContext, LintError};
-use crate::rules::get_rules;
+use crate::config::RulesConfig;
+use crate::rules::common::{
+    LineKind, LintContext, LintError, PARSER_OPTIONS, Rule, classify_lines,
+};
+use crate::rules::get_rules_with_config;
+use crate::suppression::Suppressions;
+use crate::text::{LineEnding, LineIndex, lines_with_endings};
+
+/// Rules that are called once per line instead of once per Markdown event.
+const LINE_RULES: &[&str] = &["MD012", "LINE_TOO_LONG", "LINE_ENDINGS"];
 
 /// Lints the provided Markdown content against defined rules.
 ///
@@ -25,17 +33,42 @@ use crate::rules::get_rules;
 /// assert_eq!(errors[0].message, "Empty link URL found");
 /// ```
 pub fn lint(content: &str, file_path: &Path) -> Vec<LintError> {
-    let parser = Parser::new(content);
-    let mut errors = Vec::new();
-    let rules = get_rules();
+    lint_with_config(content, file_path, &RulesConfig::default())
+}
 
-    let lines: Vec<&str> = content.lines().collect();
+/// Lints the provided Markdown content with rule settings from the config file.
+///
+/// Problems on lines covered by `<!-- md-check-disable... -->` comments are
+/// left out; see [`Suppressions`].
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use md_check::config::Config;
+/// use md_check::linter::lint_with_config;
+///
+/// let content = "Visit [the docs](http://localhost:8080/docs).";
+/// let config = Config::parse("[rules.url_hygiene]\nlocal_addresses = false\n").unwrap();
+///
+/// let errors = lint_with_config(content, Path::new("test.md"), &config.rules);
+///
+/// assert!(errors.is_empty());
+/// ```
+pub fn lint_with_config(content: &str, file_path: &Path, config: &RulesConfig) -> Vec<LintError> {
+    let parser = Parser::new_ext(content, PARSER_OPTIONS).into_offset_iter();
+    let mut errors = Vec::new();
+    let rules = get_rules_with_config(config);
+    let line_index = LineIndex::new(content);
 
     // ==========================================
-    // 1: check lines (MD012)
+    // 1: check lines (MD012, LINE_TOO_LONG, LINE_ENDINGS)
     // ==========================================
+    let first_line_ending =
+        lines_with_endings(content).find_map(|(_, ending)| LineEnding::from_terminator(ending));
+    let line_kinds = classify_lines(content);
     let mut previous_line_was_blank = false;
-    for (idx, line) in lines.iter().enumerate() {
+    for (idx, (line, ending)) in lines_with_endings(content).enumerate() {
         let current_line_number = idx + 1;
         let current_line_is_blank = line.trim().is_empty();
 
@@ -45,14 +78,16 @@ pub fn lint(content: &str, file_path: &Path) -> Vec<LintError> {
             current_line_is_blank,
             previous_line_was_blank,
             line_text: line.to_string(),
+            line_kind: line_kinds.get(idx).copied().unwrap_or_default(),
+            line_ending: LineEnding::from_terminator(ending),
+            first_line_ending,
         };
 
         let dummy_event = Event::Text("".into());
 
         for rule in &rules {
-            let rule_id = rule.id();
-            // run only rules that check lines (MD012)
-            if (rule_id == "MD012" || rule_id == "LINE_TOO_LONG")
+            // run only rules that check lines
+            if LINE_RULES.contains(&rule.id())
                 && let Some(error) = rule.check(&dummy_event, &context)
             {
                 errors.push(error);
@@ -64,18 +99,8 @@ pub fn lint(content: &str, file_path: &Path) -> Vec<LintError> {
     // ==========================================
     // 2: check AST (NO_EMPTY_LINKS etc.)
     // ==========================================
-    for (line_num, event) in (1..).zip(parser) {
-        let mut event_line = 1;
-        if let Event::Start(Tag::Link { dest_url, .. }) = &event {
-            for (idx, line) in lines.iter().enumerate() {
-                if line.contains(dest_url.as_ref()) {
-                    event_line = idx + 1;
-                    break;
-                }
-            }
-        } else {
-            event_line = line_num;
-        }
+    for (event, range) in parser {
+        let (event_line, event_column) = line_index.line_col(content, range.start);
 
         let context = LintContext {
             file_path: file_path.to_path_buf(),
@@ -83,34 +108,83 @@ pub fn lint(content: &str, file_path: &Path) -> Vec<LintError> {
             current_line_is_blank: false,
             previous_line_was_blank: false,
             line_text: String::new(),
+            line_kind: LineKind::Text,
+            line_ending: None,
+            first_line_ending,
         };
 
         for rule in &rules {
-            let rule_id = rule.id();
             // run only AST rules
-            if rule_id != "MD012"
-                && rule_id != "LINE_TOO_LONG"
+            if !LINE_RULES.contains(&rule.id())
                 && let Some(mut error) = rule.check(&event, &context)
             {
                 error.line = event_line;
+                error.column = error.column.or(Some(event_column));
                 errors.push(error);
             }
         }
     }
 
-    // deduplicate and sort errors by line number
+    // ==========================================
+    // 3: check the whole document (heading rules etc.)
+    // ==========================================
+    for rule in &rules {
+        errors.extend(rule.check_document(content, file_path));
+    }
+
+    // drop errors silenced by md-check-disable comments, which may name a
+    // rule by an alias
+    let suppressions = Suppressions::parse(content);
+    errors.retain(|e| match rules.iter().find(|rule| rule.id() == e.rule_id) {
+        Some(rule) => !rule.is_suppressed(&suppressions, e.line),
+        None => !suppressions.is_suppressed(&e.rule_id, e.line),
+    });
+
+    // deduplicate and sort errors by position
     use std::collections::HashSet;
     let mut seen = HashSet::new();
     errors.retain(|e| {
         let key = (
             e.file_path.clone(),
             e.line,
+            e.column,
             e.rule_id.clone(),
             e.message.clone(),
         );
         seen.insert(key)
     });
 
-    errors.sort_by_key(|e| e.line);
+    errors.sort_by_key(|e| (e.line, e.column));
     errors
 }
+
+/// Applies the automatic fixes of every rule that has them, in rule order.
+///
+/// Problems without an automatic fix, and problems silenced by suppression
+/// comments, are left as they are; lint the result to find out what remains.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::config::RulesConfig;
+/// use md_check::linter::fix_with_config;
+///
+/// let fixed = fix_with_config("# Title\n\n\n\nText\n", &RulesConfig::default());
+///
+/// assert_eq!(fixed, "# Title\n\nText\n");
+/// ```
+pub fn fix_with_config(content: &str, config: &RulesConfig) -> String {
+    let mut fixed = content.to_string();
+    for rule in get_rules_with_config(config) {
+        if let Some(result) = fix_rule(rule.as_ref(), &fixed) {
+            fixed = result;
+        }
+    }
+    fixed
+}
+
+/// Applies the automatic fix of `rule` to `content`, except where
+/// suppression comments silence it. Returns `None` if the rule has no fix.
+pub fn fix_rule(rule: &dyn Rule, content: &str) -> Option<String> {
+    rule.fix(content, &Suppressions::parse(content))
+}
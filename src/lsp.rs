@@ -0,0 +1,485 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tower_lsp::jsonrpc;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer, LspService, Server};
+
+use md_check::link_checker::{self, CheckResult, LinkChecker};
+use md_check::linter;
+use md_check::rules::common::LintError;
+use md_check::rules::{find_rule, get_rules_with_config};
+use md_check::suppression;
+use md_check::text::lines_with_endings;
+
+use crate::AnalysisOptions;
+
+/// Name diagnostics are attributed to in the editor.
+const SOURCE: &str = "md-check";
+
+/// How long a document has to stay unsaved before its remote links are
+/// requested, so that a burst of saves triggers a single check.
+const REMOTE_CHECK_DELAY: Duration = Duration::from_millis(500);
+
+/// An open document.
+struct Document {
+    path: PathBuf,
+    version: i32,
+    content: String,
+    /// Bumped on every save; a pending remote check is dropped when it no
+    /// longer matches.
+    saves: u64,
+    /// Results of the last remote check, by URL.
+    remote_results: HashMap<String, CheckResult>,
+}
+
+struct State {
+    options: AnalysisOptions,
+    /// `None` when link checking is disabled.
+    checker: Option<LinkChecker>,
+    documents: Mutex<HashMap<Url, Document>>,
+}
+
+#[derive(Clone)]
+struct Backend {
+    client: Client,
+    state: Arc<State>,
+}
+
+/// Runs a language server over stdin and stdout until the client exits.
+///
+/// Lint errors and local link problems are published whenever a document is
+/// opened or changed. Remote links are only requested after a save, once the
+/// document has been quiet for [`REMOTE_CHECK_DELAY`].
+pub async fn run(options: AnalysisOptions, checker: Option<LinkChecker>) -> Result<()> {
+    let state = Arc::new(State {
+        options,
+        checker,
+        documents: Mutex::new(HashMap::new()),
+    });
+    let (service, socket) = LspService::new(|client| Backend { client, state });
+    Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+        .serve(service)
+        .await;
+    Ok(())
+}
+
+impl Backend {
+    /// Publishes the current diagnostics of `uri`, if it is still open.
+    async fn publish(&self, uri: Url) {
+        let published = {
+            let documents = self.state.documents.lock().unwrap();
+            documents
+                .get(&uri)
+                .map(|document| (self.diagnostics(document), document.version))
+        };
+        if let Some((diagnostics, version)) = published {
+            self.client
+                .publish_diagnostics(uri, diagnostics, Some(version))
+                .await;
+        }
+    }
+
+    fn lint(&self, document: &Document) -> Vec<LintError> {
+        linter::lint_with_config(
+            &document.content,
+            &document.path,
+            &self.state.options.rules_config,
+        )
+    }
+
+    fn diagnostics(&self, document: &Document) -> Vec<Diagnostic> {
+        let lines: Vec<&str> = lines_with_endings(&document.content)
+            .map(|(line, _)| line)
+            .collect();
+        let mut diagnostics: Vec<Diagnostic> = self
+            .lint(document)
+            .iter()
+            .map(|error| lint_diagnostic(error, &lines))
+            .collect();
+
+        if self.state.checker.is_none() {
+            return diagnostics;
+        }
+        let mut results = link_checker::check_local_links(
+            &document.content,
+            &document.path,
+            &self.state.options.local_options,
+        );
+        // Remote results are kept across edits, so map them to where the
+        // links are now
+        results.extend(
+            link_checker::extract_links(
+                &document.content,
+                &document.path,
+                &self.state.options.local_options,
+            )
+            .into_iter()
+            .filter_map(|link| {
+                let result = document.remote_results.get(&link.url)?;
+                Some(CheckResult {
+                    link,
+                    ..result.clone()
+                })
+            }),
+        );
+        diagnostics.extend(
+            results
+                .iter()
+                .filter(|result| result.is_failure())
+                .map(|result| link_diagnostic(result, &lines)),
+        );
+        diagnostics
+    }
+
+    /// Checks the remote links of `uri` unless it is saved again within
+    /// [`REMOTE_CHECK_DELAY`].
+    async fn check_remote_links(self, uri: Url, saves: u64) {
+        tokio::time::sleep(REMOTE_CHECK_DELAY).await;
+        let Some(checker) = &self.state.checker else {
+            return;
+        };
+        let links = {
+            let documents = self.state.documents.lock().unwrap();
+            match documents.get(&uri) {
+                Some(document) if document.saves == saves => link_checker::extract_links(
+                    &document.content,
+                    &document.path,
+                    &self.state.options.local_options,
+                ),
+                _ => return,
+            }
+        };
+
+        let results = checker.check(links).await;
+        {
+            let mut documents = self.state.documents.lock().unwrap();
+            let Some(document) = documents.get_mut(&uri) else {
+                return;
+            };
+            document.remote_results = results
+                .into_iter()
+                .map(|result| (result.link.url.clone(), result))
+                .collect();
+        }
+        self.publish(uri).await;
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> jsonrpc::Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Options(
+                    TextDocumentSyncOptions {
+                        open_close: Some(true),
+                        change: Some(TextDocumentSyncKind::FULL),
+                        save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                        ..TextDocumentSyncOptions::default()
+                    },
+                )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        ..CodeActionOptions::default()
+                    },
+                )),
+                ..ServerCapabilities::default()
+            },
+            server_info: Some(ServerInfo {
+                name: SOURCE.to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn shutdown(&self) -> jsonrpc::Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let document = params.text_document;
+        let path = document
+            .uri
+            .to_file_path()
+            .unwrap_or_else(|_| PathBuf::from(document.uri.path()));
+        self.state.documents.lock().unwrap().insert(
+            document.uri.clone(),
+            Document {
+                path,
+                version: document.version,
+                content: document.text,
+                saves: 0,
+                remote_results: HashMap::new(),
+            },
+        );
+        self.publish(document.uri).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // Full sync: the last change holds the whole document
+        let Some(change) = params.content_changes.into_iter().last() else {
+            return;
+        };
+        let uri = params.text_document.uri;
+        {
+            let mut documents = self.state.documents.lock().unwrap();
+            let Some(document) = documents.get_mut(&uri) else {
+                return;
+            };
+            document.version = params.text_document.version;
+            document.content = change.text;
+        }
+        self.publish(uri).await;
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let uri = params.text_document.uri;
+        let saves = {
+            let mut documents = self.state.documents.lock().unwrap();
+            let Some(document) = documents.get_mut(&uri) else {
+                return;
+            };
+            document.saves += 1;
+            document.saves
+        };
+        tokio::spawn(self.clone().check_remote_links(uri, saves));
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.state.documents.lock().unwrap().remove(&uri);
+        self.client.publish_diagnostics(uri, Vec::new(), None).await;
+    }
+
+    async fn hover(&self, params: HoverParams) -> jsonrpc::Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let documents = self.state.documents.lock().unwrap();
+        let Some(document) = documents.get(&position.text_document.uri) else {
+            return Ok(None);
+        };
+
+        let line = position.position.line as usize + 1;
+        let mut rule_ids: Vec<String> = self
+            .lint(document)
+            .into_iter()
+            .filter(|error| error.line == line)
+            .map(|error| error.rule_id)
+            .collect();
+        rule_ids.sort();
+        rule_ids.dedup();
+        if rule_ids.is_empty() {
+            return Ok(None);
+        }
+
+        let sections: Vec<String> = get_rules_with_config(&self.state.options.rules_config)
+            .iter()
+            .filter(|rule| rule_ids.iter().any(|id| id == rule.id()))
+            .map(|rule| {
+                format!(
+                    "**{}** {}\n\n{}",
+                    rule.id(),
+                    rule.name(),
+                    rule.description()
+                )
+            })
+            .collect();
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: sections.join("\n\n---\n\n"),
+            }),
+            range: None,
+        }))
+    }
+
+    async fn code_action(
+        &self,
+        params: CodeActionParams,
+    ) -> jsonrpc::Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let documents = self.state.documents.lock().unwrap();
+        let Some(document) = documents.get(&uri) else {
+            return Ok(None);
+        };
+
+        let rules = get_rules_with_config(&self.state.options.rules_config);
+        let mut actions = Vec::new();
+        for diagnostic in &params.context.diagnostics {
+            let Some(NumberOrString::String(rule_id)) = &diagnostic.code else {
+                continue;
+            };
+            let Some(rule) = rules.iter().find(|rule| rule.id() == rule_id) else {
+                continue;
+            };
+
+            if let Some(fixed) = linter::fix_rule(rule.as_ref(), &document.content)
+                && fixed != document.content
+            {
+                let edit = TextEdit::new(whole_document(&document.content), fixed);
+                actions.push(code_action(
+                    format!("Fix all {} problems", rule_id),
+                    diagnostic,
+                    &uri,
+                    edit,
+                ));
+            }
+
+            let edit = disable_line_edit(
+                &document.content,
+                diagnostic.range.start.line as usize,
+                rule_id,
+            );
+            actions.push(code_action(
+                format!("Disable {} for this line", rule_id),
+                diagnostic,
+                &uri,
+                edit,
+            ));
+        }
+        Ok(Some(actions))
+    }
+}
+
+fn code_action(
+    title: String,
+    diagnostic: &Diagnostic,
+    uri: &Url,
+    edit: TextEdit,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+            ..WorkspaceEdit::default()
+        }),
+        ..CodeAction::default()
+    })
+}
+
+fn lint_diagnostic(error: &LintError, lines: &[&str]) -> Diagnostic {
+    let line = lines.get(error.line - 1).copied().unwrap_or_default();
+    let start = error.column.map_or(0, |column| utf16_column(line, column));
+    Diagnostic {
+        range: line_range(error.line, start, line),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(error.rule_id.clone())),
+        code_description: find_rule(&error.rule_id)
+            .and_then(|rule| Url::parse(&rule.documentation_url()).ok())
+            .map(|href| CodeDescription { href }),
+        source: Some(SOURCE.to_string()),
+        message: error.message.clone(),
+        ..Diagnostic::default()
+    }
+}
+
+fn link_diagnostic(result: &CheckResult, lines: &[&str]) -> Diagnostic {
+    let line = lines.get(result.link.line - 1).copied().unwrap_or_default();
+    let start = line.find(&result.link.url).map_or(0, |offset| {
+        utf16_column(line, line[..offset].chars().count() + 1)
+    });
+    let message = match &result.error_message {
+        Some(error) => format!(
+            "Broken link {} ({}: {})",
+            result.link.url, result.status, error
+        ),
+        None => format!("Broken link {} ({})", result.link.url, result.status),
+    };
+    Diagnostic {
+        range: line_range(result.link.line, start, line),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+/// Range from `start` to the end of the 1-based `line`, whose text is `text`.
+fn line_range(line: usize, start: u32, text: &str) -> Range {
+    let line = line.saturating_sub(1) as u32;
+    let end = text.encode_utf16().count() as u32;
+    Range::new(
+        Position::new(line, start),
+        Position::new(line, end.max(start)),
+    )
+}
+
+/// Converts a 1-based column counted in characters, as in [`LintError`], to
+/// the 0-based UTF-16 offset LSP positions use.
+fn utf16_column(line: &str, column: usize) -> u32 {
+    line.chars()
+        .take(column.saturating_sub(1))
+        .map(char::len_utf16)
+        .sum::<usize>() as u32
+}
+
+/// Range covering all of `content`.
+fn whole_document(content: &str) -> Range {
+    let mut end = Position::new(0, 0);
+    for (line, ending) in lines_with_endings(content) {
+        if ending.is_empty() {
+            end.character = line.encode_utf16().count() as u32;
+        } else {
+            end = Position::new(end.line + 1, 0);
+        }
+    }
+    Range::new(Position::new(0, 0), end)
+}
+
+/// Appends a comment disabling `rule_id` to the 0-based `line`. Unlike a
+/// comment on a line of its own, it cannot split a paragraph or list item.
+/// Trailing spaces stay at the end, so a hard line break is kept.
+fn disable_line_edit(content: &str, line: usize, rule_id: &str) -> TextEdit {
+    let text = lines_with_endings(content)
+        .nth(line)
+        .map_or("", |(text, _)| text);
+    let trimmed = text.trim_end();
+    let separator = if trimmed.is_empty() { "" } else { " " };
+
+    let position = Position::new(line as u32, trimmed.encode_utf16().count() as u32);
+    TextEdit::new(
+        Range::new(position, position),
+        format!(
+            "{}{}",
+            separator,
+            suppression::disable_line_comment(rule_id)
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_use_utf16_offsets() {
+        assert_eq!(utf16_column("naïve 😀 text", 9), 9);
+        assert_eq!(
+            whole_document("# Título\r\n\r\n😀"),
+            Range::new(Position::new(0, 0), Position::new(2, 2))
+        );
+        assert_eq!(
+            whole_document("text\n"),
+            Range::new(Position::new(0, 0), Position::new(1, 0))
+        );
+    }
+
+    #[test]
+    fn test_disable_line_edit_appends_to_line() {
+        let edit = disable_line_edit("- item\r\n  <b>naïve</b>  \r\n", 1, "NO_HTML");
+
+        assert_eq!(edit.range.start, Position::new(1, 14));
+        assert_eq!(edit.range.end, edit.range.start);
+        assert_eq!(edit.new_text, " <!-- md-check-disable-line NO_HTML -->");
+
+        let edit = disable_line_edit("# Title\n\n\n\ntext\n", 2, "MD012");
+        assert_eq!(edit.range.start, Position::new(2, 0));
+        assert_eq!(edit.new_text, "<!-- md-check-disable-line MD012 -->");
+    }
+}
@@ -1,105 +1,1139 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
+use futures::stream;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tokio::sync::mpsc;
+use tokio::task;
 
-use md_check::link_checker;
+use md_check::baseline::{self, Baseline, Fingerprint};
+use md_check::cache::{LinkCache, LintCache};
+use md_check::config::{CONFIG_FILE_NAME, Config, RulesConfig, STARTER_CONFIG};
+use md_check::diff::{self, ChangedLines, DiffTarget};
+use md_check::discovery::FileDiscovery;
+use md_check::link_checker::{
+    self, CheckResult, ClientOptions, LinkChecker, LinkCheckerBuilder, LinkInfo, LocalCheckOptions,
+};
 use md_check::linter;
+use md_check::markdownlint::MarkdownlintConfig;
+use md_check::reporter::ConsoleReporter;
+use md_check::rules::common::{LintError, Rule};
+use md_check::rules::{self, UrlHygieneRule, all_rules, find_rule};
+use md_check::suppression::Suppressions;
+use md_check::text::{self, Encoding};
+
+mod lsp;
+mod watch;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Without a subcommand, the arguments of `check`
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Lint files and check their links (the default)
+    Check(Args),
+    /// Lint files without checking links
+    Lint(Args),
+    /// Check links without linting
+    Links(Args),
+    /// Apply automatic fixes, then report the problems that remain
+    Fix(Args),
+    /// Run as a language server over stdin and stdout
+    // The server takes no paths, so drop the requirement inherited from `Args`
+    #[command(mut_arg("paths", |arg| {
+        arg.required_unless_present(clap::builder::Resettable::<clap::Id>::Reset)
+    }))]
+    Lsp(Args),
+    /// List every rule with its ID, name, default state and description
+    Rules,
+    /// Show the documentation of a rule, with examples
+    Explain {
+        /// Rule ID, such as MD012 or NO_HTML
+        rule: String,
+    },
+    /// Write a starter .md-check.toml to the current directory
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
-    /// Files or directories to check
-    #[arg(required = true)]
+    /// Files or directories to check; `-` reads a document from stdin
+    #[arg(required_unless_present = "lsp")]
     paths: Vec<PathBuf>,
 
+    /// Path of the stdin document, used in diagnostics, for config lookup
+    /// and to resolve relative links
+    #[arg(long, value_name = "PATH")]
+    stdin_filename: Option<PathBuf>,
+
+    /// Apply automatic fixes to the checked files; with `-`, write the fixed
+    /// document to stdout instead
+    #[arg(long)]
+    fix: bool,
+
     /// Skip link checking
     #[arg(long, default_value = "false")]
     skip_links: bool,
+
+    /// Skip linting; set by the `links` subcommand
+    #[arg(skip)]
+    skip_lint: bool,
+
+    /// Validate only local links and URL syntax; remote links are served from
+    /// the link cache or reported as unchecked
+    #[arg(long, conflicts_with = "skip_links")]
+    offline: bool,
+
+    /// Record remote link results for later offline runs and skip re-linting
+    /// unchanged files, using the cache directory
+    #[arg(long)]
+    cache: bool,
+
+    /// Keep running and re-check files as they change
+    #[arg(long, conflicts_with = "fix")]
+    watch: bool,
+
+    /// Only report problems on lines changed since this git commit, branch or
+    /// tag, and only check links on those lines
+    #[arg(long, value_name = "REF", conflicts_with_all = ["fix", "watch"])]
+    diff_base: Option<String>,
+
+    /// Lint the content staged for commit and only report problems on the
+    /// staged lines, for pre-commit hooks
+    #[arg(long, conflicts_with_all = ["diff_base", "fix", "watch"])]
+    staged: bool,
+
+    /// Record the problems found in this baseline file, which --baseline then
+    /// accepts, and exit successfully
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["watch", "lsp"])]
+    write_baseline: Option<PathBuf>,
+
+    /// Only report problems that are not recorded in this baseline file
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["watch", "lsp", "write_baseline"])]
+    baseline: Option<PathBuf>,
+
+    /// Run as a language server over stdin and stdout
+    #[arg(long, conflicts_with_all = ["paths", "fix", "watch", "diff_base", "staged"])]
+    lsp: bool,
+
+    /// Print additional details, such as each file analyzed and lint cache hits
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Treat files with this extension as Markdown, in addition to the
+    /// configured ones (repeatable)
+    #[arg(long = "extension", value_name = "EXT")]
+    extensions: Vec<String>,
+
+    /// Also check files ignored by .gitignore, .ignore and git exclude files
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Descend into hidden directories and check hidden files
+    #[arg(long)]
+    hidden: bool,
+
+    /// Only check files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip files and directories matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Number of files to lint in parallel (defaults to the number of CPU cores)
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+
+    /// Run this rule, or the rules with this tag (repeatable)
+    #[arg(long, value_name = "RULE|TAG")]
+    enable: Vec<String>,
+
+    /// Do not run this rule, or the rules with this tag (repeatable)
+    #[arg(long, value_name = "RULE|TAG")]
+    disable: Vec<String>,
+
+    /// Path to the config file (defaults to the nearest .md-check.toml)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Total timeout for each link request, in seconds
+    #[arg(long, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Timeout for establishing a connection, in seconds
+    #[arg(long, value_name = "SECS")]
+    connect_timeout: Option<u64>,
+
+    /// Timeout for each read from the connection, in seconds
+    #[arg(long, value_name = "SECS")]
+    read_timeout: Option<u64>,
+
+    /// User agent sent with link requests
+    #[arg(long)]
+    user_agent: Option<String>,
+
+    /// Proxy used for http:// links
+    #[arg(long, value_name = "URL")]
+    http_proxy: Option<String>,
+
+    /// Proxy used for https:// links
+    #[arg(long, value_name = "URL")]
+    https_proxy: Option<String>,
+
+    /// Hosts that bypass the proxy (comma-separated)
+    #[arg(long, value_name = "HOSTS", value_delimiter = ',')]
+    no_proxy: Vec<String>,
+
+    /// PEM file with additional trusted CA certificates
+    #[arg(long, value_name = "FILE")]
+    ca_bundle: Option<PathBuf>,
+
+    /// Skip TLS certificate verification for this host (repeatable)
+    #[arg(long, value_name = "HOST")]
+    insecure_host: Vec<String>,
+
+    /// Allow links with this URL scheme in addition to the configured ones (repeatable)
+    #[arg(long, value_name = "SCHEME")]
+    allow_scheme: Vec<String>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args = Args::parse();
-    let mut markdown_files = Vec::new();
+impl Args {
+    fn reads_stdin(&self) -> bool {
+        self.paths.iter().any(|path| path.as_os_str() == STDIN_PATH)
+    }
 
-    // Find all Markdown files
-    for path in args.paths {
-        if path.is_dir() {
-            for entry in walkdir::WalkDir::new(path)
-                .into_iter()
-                .filter_map(Result::ok)
-                .filter(|e| !e.file_type().is_dir())
-                .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
-            {
-                markdown_files.push(entry.path().to_path_buf());
+    /// The path stdin diagnostics are reported under.
+    fn stdin_path(&self) -> PathBuf {
+        self.stdin_filename
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(STDIN_NAME))
+    }
+
+    fn diff_target(&self) -> Option<DiffTarget> {
+        match &self.diff_base {
+            Some(base) => Some(DiffTarget::Base(base.clone())),
+            None => self.staged.then_some(DiffTarget::Staged),
+        }
+    }
+
+    /// Loads the config file and applies command-line overrides on top of it.
+    ///
+    /// The config file is looked up from the directory of `--stdin-filename`
+    /// when reading stdin, and from the working directory otherwise.
+    fn load_config(&self) -> Result<Config> {
+        let mut start = std::env::current_dir()?;
+        if self.reads_stdin()
+            && let Some(dir) = self.stdin_filename.as_deref().and_then(Path::parent)
+        {
+            start = start.join(dir);
+        }
+        let path = match &self.config {
+            Some(path) => Some(path.clone()),
+            None => Config::discover(&start),
+        };
+
+        // Settings from a markdownlint file apply first, so that md-check's
+        // own config can refine them
+        let mut rules = RulesConfig::default();
+        if let Some(path) = MarkdownlintConfig::discover(&start) {
+            for warning in MarkdownlintConfig::load(&path)?.apply(&mut rules) {
+                eprintln!("{}: {} - {}", "Warning".yellow(), path.display(), warning);
             }
-        } else if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
-            markdown_files.push(path);
         }
+        let mut config = match path {
+            Some(path) => Config::load_over(&path, rules)?,
+            None => Config {
+                rules,
+                ..Config::default()
+            },
+        };
+
+        let files = &mut config.files;
+        files.extensions.extend(self.extensions.iter().cloned());
+        files.include.extend(self.include.iter().cloned());
+        files.exclude.extend(self.exclude.iter().cloned());
+        files.respect_ignore_files &= !self.no_ignore;
+        files.hidden |= self.hidden;
+
+        let links = &mut config.links;
+        links.timeout = self.timeout.or(links.timeout);
+        links.connect_timeout = self.connect_timeout.or(links.connect_timeout);
+        links.read_timeout = self.read_timeout.or(links.read_timeout);
+        if let Some(user_agent) = &self.user_agent {
+            links.user_agent = Some(user_agent.clone());
+        }
+        if let Some(proxy) = &self.http_proxy {
+            links.http_proxy = Some(proxy.clone());
+        }
+        if let Some(proxy) = &self.https_proxy {
+            links.https_proxy = Some(proxy.clone());
+        }
+        if !self.no_proxy.is_empty() {
+            links.no_proxy = self.no_proxy.clone();
+        }
+        if let Some(ca_bundle) = &self.ca_bundle {
+            links.ca_bundle = Some(ca_bundle.clone());
+        }
+        links
+            .insecure_hosts
+            .extend(self.insecure_host.iter().cloned());
+        if !self.allow_scheme.is_empty() {
+            links
+                .allowed_schemes
+                .get_or_insert_with(|| LocalCheckOptions::default().allowed_schemes)
+                .extend(self.allow_scheme.iter().cloned());
+        }
+        config.cache.enabled |= self.cache;
+
+        // Command-line choices replace the opposite choice from the config
+        let rules = &mut config.rules;
+        for name in &self.enable {
+            rules.choose_enabled(name);
+        }
+        for name in &self.disable {
+            rules.choose_disabled(name);
+        }
+        rules::validate_selection(rules)?;
+
+        Ok(config)
     }
+}
 
-    if markdown_files.is_empty() {
-        println!("{}", "No Markdown files found.".yellow());
-        return Ok(());
+/// Path argument that reads a document from stdin.
+const STDIN_PATH: &str = "-";
+
+/// Name used for stdin in diagnostics when `--stdin-filename` is not given.
+const STDIN_NAME: &str = "<stdin>";
+
+/// Capacity of the channels between the discovery, analysis and link
+/// checking stages.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// What the analysis stage collects besides the links it forwards.
+#[derive(Default)]
+struct Analysis {
+    lint_errors: Vec<LintError>,
+    local_results: Vec<CheckResult>,
+    /// Text of the lines with problems or links, by file and line, for
+    /// baseline fingerprints.
+    lines: HashMap<(PathBuf, usize), String>,
+    /// Suppression comments by file, for diagnostics reported after linting.
+    suppressions: HashMap<PathBuf, Suppressions>,
+}
+
+impl Analysis {
+    fn keep_lines(&mut self, file_path: &Path, content: &str, lines: &HashSet<usize>) {
+        for (idx, (line, _)) in text::lines_with_endings(content).enumerate() {
+            if lines.contains(&(idx + 1)) {
+                self.lines
+                    .insert((file_path.to_path_buf(), idx + 1), line.to_string());
+            }
+        }
     }
 
-    println!(
-        "{} {} Markdown files.",
-        "Found".green(),
-        markdown_files.len()
-    );
+    /// Fingerprints a problem for `--baseline` and `--write-baseline`.
+    fn fingerprint(
+        &self,
+        rule_id: &str,
+        file_path: &Path,
+        line: usize,
+        url: Option<&str>,
+    ) -> Fingerprint {
+        let text = self
+            .lines
+            .get(&(file_path.to_path_buf(), line))
+            .map_or("", String::as_str);
+        Fingerprint::new(rule_id, file_path, text, url)
+    }
+}
 
-    let mut all_links = Vec::new();
-    let mut lint_errors = Vec::new();
+/// Settings shared by the analysis workers.
+struct AnalysisOptions {
+    rules_config: RulesConfig,
+    local_options: LocalCheckOptions,
+    jobs: usize,
+    verbose: bool,
+    fix: bool,
+    /// With `--diff-base` or `--staged`, the lines problems are reported on.
+    changed_lines: Option<Arc<ChangedLines>>,
+    /// Whether to keep the text of lines with problems for baselines.
+    keep_lines: bool,
+    skip_lint: bool,
+    /// Whether remote links are probed for HTTPS versions on behalf of
+    /// URL_HYGIENE, which needs the suppression comments of each file kept.
+    https_upgrades: bool,
+}
 
-    // Analyze files
-    for file_path in &markdown_files {
-        println!("{} {}", "Analyzing".cyan(), file_path.display());
-        match fs::read_to_string(file_path) {
-            Ok(content) => {
-                let errors = linter::lint(&content, file_path);
-                lint_errors.extend(errors);
+impl AnalysisOptions {
+    fn new(args: &Args, config: &Config) -> Self {
+        Self {
+            rules_config: config.rules.clone(),
+            local_options: LocalCheckOptions::from_config(config),
+            jobs: args.jobs.map(usize::from).unwrap_or_else(|| {
+                thread::available_parallelism()
+                    .map(usize::from)
+                    .unwrap_or(1)
+            }),
+            verbose: args.verbose,
+            fix: args.fix,
+            changed_lines: None,
+            keep_lines: args.baseline.is_some() || args.write_baseline.is_some(),
+            skip_lint: args.skip_lint,
+            https_upgrades: !args.skip_links
+                && !args.skip_lint
+                && config.rules.url_hygiene.insecure_http
+                && rules::is_enabled(&url_hygiene_rule(config), &config.rules),
+        }
+    }
+}
 
-                if !args.skip_links {
-                    let links = link_checker::extract_links(&content, file_path);
-                    all_links.extend(links);
-                }
+/// A document to analyze.
+enum Source {
+    File(PathBuf),
+    /// The staged content of a file, for `--staged`.
+    Index(PathBuf),
+    /// Content read from stdin, analyzed as if it were stored at `path`.
+    Stdin {
+        path: PathBuf,
+        content: Vec<u8>,
+    },
+}
+
+/// Sends every Markdown file under `paths` to `files` and returns how many
+/// were found. A `-` path sends the document read from stdin as `stdin_path`.
+/// With `changed_lines`, files without changes are left out; with `staged`,
+/// the others are read from the index.
+fn discover_files(
+    discovery: &FileDiscovery,
+    paths: &[PathBuf],
+    stdin_path: PathBuf,
+    changed_lines: Option<&ChangedLines>,
+    staged: bool,
+    files: &mpsc::Sender<Source>,
+) -> Result<usize> {
+    let mut count = 0;
+    // The receiver only goes away if the analysis stage panicked
+    let mut send = |source: Source| {
+        count += 1;
+        let _ = files.blocking_send(source);
+    };
+
+    let (stdin, paths): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .iter()
+        .cloned()
+        .partition(|path| path.as_os_str() == STDIN_PATH);
+    if !stdin.is_empty() {
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content)?;
+        send(Source::Stdin {
+            path: stdin_path,
+            content,
+        });
+    }
+    discovery.discover(&paths, |path| {
+        if !changed_lines.is_none_or(|changed| changed.contains_file(&path)) {
+            return;
+        }
+        send(if staged {
+            Source::Index(path)
+        } else {
+            Source::File(path)
+        })
+    });
+
+    if count > 0 {
+        println!("{} {} Markdown files.", "Found".green(), count);
+    }
+    Ok(count)
+}
+
+/// Reads, fixes and lints single documents. Shared by the analysis workers.
+struct Analyzer {
+    options: AnalysisOptions,
+    lint_cache: Option<Mutex<LintCache>>,
+}
+
+impl Analyzer {
+    fn new(options: AnalysisOptions, lint_cache: Option<LintCache>) -> Self {
+        Self {
+            options,
+            lint_cache: lint_cache.map(Mutex::new),
+        }
+    }
+
+    /// Reads and decodes `source`, applying fixes if `--fix` is set. Returns
+    /// the path and content to lint, or `None` if the document could not be
+    /// read; encoding problems are added to `lint_errors`.
+    fn read(&self, source: Source, lint_errors: &mut Vec<LintError>) -> Option<(PathBuf, String)> {
+        let (file_path, bytes) = match source {
+            Source::File(path) => {
+                let bytes = fs::read(&path);
+                (path, bytes)
+            }
+            Source::Index(path) => {
+                let bytes = diff::staged_content(&path).map_err(io::Error::other);
+                (path, bytes)
+            }
+            Source::Stdin { path, content } => (path, Ok(content)),
+        };
+
+        let decoded = match bytes.map(|bytes| text::decode(&bytes)) {
+            Ok(Ok(decoded)) => decoded,
+            Ok(Err(e)) => {
+                let (line, column) = e.position();
+                lint_errors.push(LintError {
+                    file_path,
+                    line,
+                    column,
+                    message: e.to_string(),
+                    rule_id: "ENCODING".to_string(),
+                });
+                return None;
             }
             Err(e) => {
                 eprintln!("{}: {} - {}", "Error".red(), file_path.display(), e);
+                return None;
+            }
+        };
+        match fix_file(&file_path, decoded, &self.options) {
+            Ok(content) => Some((file_path, content)),
+            Err(e) => {
+                eprintln!("{}: {} - {}", "Error".red(), file_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Lints `content`, taking the diagnostics from the lint cache when the
+    /// content is unchanged since the last run.
+    fn lint(&self, file_path: &Path, content: &str) -> Vec<LintError> {
+        if self.options.skip_lint {
+            return Vec::new();
+        }
+        let rules_config = &self.options.rules_config;
+        let Some(cache) = &self.lint_cache else {
+            return linter::lint_with_config(content, file_path, rules_config);
+        };
+
+        let cached = cache
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(file_path, content).map(<[LintError]>::to_vec));
+        if let Some(errors) = cached {
+            if self.options.verbose {
+                println!(
+                    "{} {} (lint cache hit)",
+                    "Cached".green(),
+                    file_path.display()
+                );
+            }
+            return errors;
+        }
+
+        let errors = linter::lint_with_config(content, file_path, rules_config);
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(file_path, content, &errors);
+        }
+        errors
+    }
+
+    /// Whether problems on `line` of `path` are reported, which is all of
+    /// them unless only changed lines are checked.
+    fn in_diff(&self, path: &Path, line: usize) -> bool {
+        self.options
+            .changed_lines
+            .as_ref()
+            .is_none_or(|changed| changed.contains(path, line))
+    }
+
+    /// Hands back the lint cache, updated with this run's diagnostics.
+    fn into_lint_cache(self) -> Option<LintCache> {
+        self.lint_cache.and_then(|cache| cache.into_inner().ok())
+    }
+}
+
+/// Lints each file received from `files` on `jobs` worker threads and
+/// forwards remote links to `links`. Local links are checked right away.
+fn analyze_files(
+    files: mpsc::Receiver<Source>,
+    links: Option<mpsc::Sender<LinkInfo>>,
+    analyzer: &Analyzer,
+) -> Analysis {
+    let files = Mutex::new(files);
+    let next_file = || files.lock().ok()?.blocking_recv();
+
+    let worker = || {
+        let mut analysis = Analysis::default();
+
+        while let Some(source) = next_file() {
+            let path = match &source {
+                Source::File(path) | Source::Index(path) | Source::Stdin { path, .. } => path,
+            };
+            // Workers pick files up in any order, so only -v shows progress
+            if analyzer.options.verbose {
+                println!("{} {}", "Analyzing".cyan(), path.display());
+            }
+
+            let mut lint_errors = Vec::new();
+            let read = analyzer.read(source, &mut lint_errors);
+            if let Some((file_path, content)) = &read {
+                lint_errors.extend(analyzer.lint(file_path, content));
             }
+            lint_errors.retain(|error| analyzer.in_diff(&error.file_path, error.line));
+            let Some((file_path, content)) = read else {
+                analysis.lint_errors.extend(lint_errors);
+                continue;
+            };
+            let mut problem_lines: HashSet<usize> =
+                lint_errors.iter().map(|error| error.line).collect();
+            analysis.lint_errors.extend(lint_errors);
+
+            if let Some(links) = &links {
+                for link in link_checker::extract_links(
+                    &content,
+                    &file_path,
+                    &analyzer.options.local_options,
+                ) {
+                    if analyzer.in_diff(&link.file_path, link.line) {
+                        problem_lines.insert(link.line);
+                        let _ = links.blocking_send(link);
+                    }
+                }
+                let local_results: Vec<CheckResult> = link_checker::check_local_links(
+                    &content,
+                    &file_path,
+                    &analyzer.options.local_options,
+                )
+                .into_iter()
+                .filter(|result| analyzer.in_diff(&result.link.file_path, result.link.line))
+                .collect();
+                problem_lines.extend(local_results.iter().map(|result| result.link.line));
+                analysis.local_results.extend(local_results);
+            }
+
+            if analyzer.options.keep_lines {
+                analysis.keep_lines(&file_path, &content, &problem_lines);
+            }
+            if analyzer.options.https_upgrades {
+                analysis
+                    .suppressions
+                    .insert(file_path, Suppressions::parse(&content));
+            }
+        }
+        analysis
+    };
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..analyzer.options.jobs.max(1))
+            .map(|_| scope.spawn(worker))
+            .collect();
+
+        let mut analysis = Analysis::default();
+        for handle in workers {
+            let part = handle.join().expect("analysis worker panicked");
+            analysis.lint_errors.extend(part.lint_errors);
+            analysis.local_results.extend(part.local_results);
+            analysis.lines.extend(part.lines);
+            analysis.suppressions.extend(part.suppressions);
         }
+        analysis
+    })
+}
+
+/// The URL_HYGIENE rule as configured, which also reports links whose HTTPS
+/// version is available once remote links have been checked.
+fn url_hygiene_rule(config: &Config) -> UrlHygieneRule {
+    UrlHygieneRule::with_config(config.rules.url_hygiene.clone())
+}
+
+/// Applies automatic fixes to `content` and writes them back to `file_path`
+/// in its original encoding if `--fix` is set and anything changed. Returns
+/// the content to lint.
+fn fix_file(
+    file_path: &Path,
+    (content, encoding): (String, Encoding),
+    options: &AnalysisOptions,
+) -> io::Result<String> {
+    if !options.fix {
+        return Ok(content);
+    }
+
+    let fixed = linter::fix_with_config(&content, &options.rules_config);
+    if fixed == content {
+        return Ok(content);
+    }
+    fs::write(file_path, text::encode(&fixed, encoding))?;
+    println!("{} {}", "Fixed".green(), file_path.display());
+    Ok(fixed)
+}
+
+/// Fixes the document read from stdin and writes it to stdout, so that
+/// editors can replace their buffer with the output. Problems that remain
+/// are reported on stderr.
+fn fix_stdin(args: &Args, config: &Config) -> Result<()> {
+    if args.paths.len() > 1 {
+        anyhow::bail!("--fix with `-` reads a single document; do not pass other paths");
+    }
+
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes)?;
+    let (content, encoding) = text::decode(&bytes)
+        .map_err(|e| anyhow::anyhow!("{}: {}", args.stdin_path().display(), e))?;
+    let fixed = linter::fix_with_config(&content, &config.rules);
+    io::stdout().write_all(&text::encode(&fixed, encoding))?;
+
+    let errors = linter::lint_with_config(&fixed, &args.stdin_path(), &config.rules);
+    for error in &errors {
+        eprintln!("{}", format_lint_error(error));
+    }
+    if !errors.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Configures a link checker from the command line and config file.
+fn link_checker_builder(args: &Args, config: &Config) -> Result<LinkCheckerBuilder> {
+    let mut builder = LinkChecker::builder()
+        .client_options(ClientOptions::from_config(&config.links))
+        .offline(args.offline);
+    if args.offline {
+        builder = builder.cache(load_link_cache(args, config)?);
+    }
+    Ok(builder)
+}
+
+/// Loads the link cache, reporting a discarded corrupt cache file under
+/// `--verbose`.
+fn load_link_cache(args: &Args, config: &Config) -> Result<LinkCache> {
+    let cache = LinkCache::load(&config.cache.dir)?;
+    if args.verbose
+        && let Some(error) = cache.load_error()
+    {
+        println!("{}", format!("Link cache discarded: {error}").yellow());
+    }
+    Ok(cache)
+}
+
+/// Runs `--watch` mode. The report is redrawn after every change, so link
+/// progress is not printed.
+async fn watch_paths(args: &Args, config: &Config) -> Result<()> {
+    if args.reads_stdin() {
+        anyhow::bail!("--watch cannot read from stdin");
+    }
+
+    let discovery = FileDiscovery::new(&config.files)?;
+    let analyzer = Analyzer::new(AnalysisOptions::new(args, config), None);
+    let checker = if args.skip_links {
+        None
+    } else {
+        Some(link_checker_builder(args, config)?.build()?)
+    };
+    watch::run(&args.paths, &discovery, &analyzer, checker.as_ref()).await
+}
+
+/// Runs the language server, `md-check lsp` or `--lsp`. `--skip-links` turns off link validation in the editor,
+/// `--offline` only the remote requests.
+async fn serve_lsp(args: &Args, config: &Config) -> Result<()> {
+    let checker = if args.skip_links {
+        None
+    } else {
+        Some(link_checker_builder(args, config)?.build()?)
+    };
+    lsp::run(AnalysisOptions::new(args, config), checker).await
+}
+
+/// Prints the `rules` table.
+fn list_rules() {
+    let rules = all_rules(&RulesConfig::default());
+    let rows: Vec<[String; 7]> = rules
+        .iter()
+        .map(|rule| {
+            [
+                rule.id().to_string(),
+                rule.name().to_string(),
+                rule.aliases().join(", "),
+                rule.tags().join(", "),
+                if rule.enabled_by_default() {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+                .to_string(),
+                if rule.fixable() { "yes" } else { "no" }.to_string(),
+                rule.description().to_string(),
+            ]
+        })
+        .collect();
+    let header = [
+        "ID",
+        "NAME",
+        "ALIASES",
+        "TAGS",
+        "DEFAULT",
+        "FIX",
+        "DESCRIPTION",
+    ]
+    .map(String::from);
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[column].len())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// Prints the documentation of a rule, found by ID or alias.
+fn explain_rule(name: &str) -> Result<()> {
+    let Some(rule) = find_rule(name) else {
+        anyhow::bail!("unknown rule '{}'; run `md-check rules` to list them", name);
+    };
+
+    println!("{} {}\n", rule.id().yellow(), rule.name().bold());
+    if !rule.aliases().is_empty() {
+        println!("Aliases: {}", rule.aliases().join(", "));
+    }
+    println!("Tags: {}", rule.tags().join(", "));
+    println!("Fixable: {}", if rule.fixable() { "yes" } else { "no" });
+    println!("Documentation: {}\n", rule.documentation_url());
+    println!("{}", rule.explanation());
+    Ok(())
+}
+
+/// Writes the starter config to the current directory.
+fn init_config(force: bool) -> Result<()> {
+    let path = PathBuf::from(CONFIG_FILE_NAME);
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; use --force to overwrite it",
+            CONFIG_FILE_NAME
+        );
+    }
+    fs::write(&path, STARTER_CONFIG)?;
+    println!("{} {}", "Created".green(), path.display());
+    Ok(())
+}
+
+fn format_fingerprint(fingerprint: &Fingerprint, count: usize) -> String {
+    let mut formatted = format!(
+        "- [{}] {}: {}",
+        fingerprint.rule_id.yellow(),
+        fingerprint.file,
+        fingerprint.url.as_deref().unwrap_or(&fingerprint.content)
+    );
+    if count > 1 {
+        formatted.push_str(&format!(" ({} times)", count));
+    }
+    formatted
+}
+
+fn format_lint_error(error: &LintError) -> String {
+    let position = match error.column {
+        Some(column) => format!("{}:{}", error.line, column),
+        None => error.line.to_string(),
+    };
+    format!(
+        "[{}] {}:{} {}",
+        error.rule_id.yellow(),
+        error.file_path.display(),
+        position,
+        error.message
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let args = match cli.command {
+        None => cli.args,
+        Some(Command::Check(args)) => args,
+        Some(Command::Lint(args)) => Args {
+            skip_links: true,
+            ..args
+        },
+        Some(Command::Links(args)) => Args {
+            skip_lint: true,
+            ..args
+        },
+        Some(Command::Fix(args)) => {
+            if args.watch || args.lsp || args.diff_base.is_some() || args.staged {
+                anyhow::bail!(
+                    "fix cannot be combined with --watch, --lsp, --diff-base or --staged"
+                );
+            }
+            Args { fix: true, ..args }
+        }
+        Some(Command::Lsp(args)) => {
+            if !args.paths.is_empty()
+                || args.fix
+                || args.watch
+                || args.diff_base.is_some()
+                || args.staged
+            {
+                anyhow::bail!(
+                    "lsp takes no paths and cannot be combined with --fix, --watch, --diff-base or --staged"
+                );
+            }
+            Args { lsp: true, ..args }
+        }
+        Some(Command::Rules) => {
+            list_rules();
+            return Ok(());
+        }
+        Some(Command::Explain { rule }) => return explain_rule(&rule),
+        Some(Command::Init { force }) => return init_config(force),
+    };
+    let config = args.load_config()?;
+
+    if args.fix && args.reads_stdin() {
+        return fix_stdin(&args, &config);
+    }
+    if args.lsp {
+        return serve_lsp(&args, &config).await;
+    }
+    if args.watch {
+        return watch_paths(&args, &config).await;
+    }
+
+    // The stages below run concurrently: files are linted while discovery is
+    // still walking directories, and link requests start as soon as the first
+    // links are extracted.
+    let (file_tx, file_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let (link_tx, link_rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let changed_lines = match args.diff_target() {
+        Some(target) => Some(Arc::new(ChangedLines::from_git(
+            &std::env::current_dir()?,
+            &target,
+        )?)),
+        None => None,
+    };
+
+    let paths = args.paths.clone();
+    let stdin_path = args.stdin_path();
+    let file_discovery = FileDiscovery::new(&config.files)?;
+    let discovery_changes = changed_lines.clone();
+    let discovery = task::spawn_blocking(move || {
+        discover_files(
+            &file_discovery,
+            &paths,
+            stdin_path,
+            discovery_changes.as_deref(),
+            args.staged,
+            &file_tx,
+        )
+    });
+
+    let lint_cache = if config.cache.enabled {
+        let cache = LintCache::load(&config.cache.dir, &config.rules)?;
+        if args.verbose
+            && let Some(error) = cache.load_error()
+        {
+            println!("{}", format!("Lint cache discarded: {error}").yellow());
+        }
+        if args.verbose && cache.invalidated() {
+            println!(
+                "{}",
+                "Lint cache discarded: md-check version or rule configuration changed.".yellow()
+            );
+        }
+        Some(cache)
+    } else {
+        None
+    };
+    let options = AnalysisOptions {
+        changed_lines,
+        ..AnalysisOptions::new(&args, &config)
+    };
+    let https_upgrades = options.https_upgrades;
+    let link_tx = (!args.skip_links).then_some(link_tx);
+    let analysis = task::spawn_blocking(move || {
+        let analyzer = Analyzer::new(options, lint_cache);
+        let analysis = analyze_files(file_rx, link_tx, &analyzer);
+        (analysis, analyzer.into_lint_cache())
+    });
+
+    let mut checker = None;
+    let mut remote = Vec::new();
+    if !args.skip_links {
+        let link_checker = link_checker_builder(&args, &config)?
+            .reporter(ConsoleReporter::new())
+            .build()?;
+
+        let mut link_rx = link_rx;
+        let links = stream::poll_fn(move |cx| link_rx.poll_recv(cx));
+        remote = link_checker.check_stream(links).await;
+        checker = Some(link_checker);
+    }
+
+    let file_count = discovery.await??;
+    let (mut analysis, lint_cache) = analysis.await?;
+    let mut lint_errors = std::mem::take(&mut analysis.lint_errors);
+    let local_results = std::mem::take(&mut analysis.local_results);
+
+    if let Some(cache) = lint_cache {
+        cache.save()?;
+    }
+
+    if file_count == 0 {
+        println!("{}", "No Markdown files found.".yellow());
+        return Ok(());
     }
 
     // Check links if not skipped
-    if !args.skip_links && !all_links.is_empty() {
-        let results = link_checker::check_links(all_links).await;
-        let formatted_errors = link_checker::format_check_results(&results);
+    let mut link_failures = Vec::new();
+    if let Some(checker) = checker {
+        if config.cache.enabled && !args.offline && !remote.is_empty() {
+            let mut cache = load_link_cache(&args, &config)?;
+            cache.update(&remote);
+            cache.save()?;
+        }
 
-        if !formatted_errors.is_empty() {
-            println!("\n{}", "Problematic links:".red());
-            for error in formatted_errors {
-                println!("{}", error);
+        if https_upgrades {
+            let rule = url_hygiene_rule(&config);
+            for (link, https_url) in checker.find_https_upgrades(&remote).await {
+                let suppressed = analysis
+                    .suppressions
+                    .get(&link.file_path)
+                    .is_some_and(|suppressions| rule.is_suppressed(suppressions, link.line));
+                if suppressed {
+                    continue;
+                }
+                lint_errors.push(LintError {
+                    file_path: link.file_path,
+                    line: link.line,
+                    column: None,
+                    message: format!("HTTPS is available, use {}", https_url),
+                    rule_id: rule.id().to_string(),
+                });
             }
         }
+
+        link_failures = local_results;
+        link_failures.extend(remote);
+        link_failures.retain(CheckResult::is_failure);
+        link_failures.sort_by(|a, b| {
+            (&a.link.file_path, a.link.line, &a.link.url).cmp(&(
+                &b.link.file_path,
+                b.link.line,
+                &b.link.url,
+            ))
+        });
+    }
+
+    // Workers finish in any order; the report must not depend on it
+    lint_errors.sort_by(|a, b| {
+        (&a.file_path, a.line, a.column, &a.rule_id, &a.message).cmp(&(
+            &b.file_path,
+            b.line,
+            b.column,
+            &b.rule_id,
+            &b.message,
+        ))
+    });
+
+    let lint_fingerprint = |error: &LintError| {
+        analysis.fingerprint(&error.rule_id, &error.file_path, error.line, None)
+    };
+    let link_fingerprint = |result: &CheckResult| {
+        analysis.fingerprint(
+            baseline::LINK_RULE_ID,
+            &result.link.file_path,
+            result.link.line,
+            Some(&result.link.url),
+        )
+    };
+
+    if let Some(path) = &args.write_baseline {
+        let baseline = Baseline::from_fingerprints(
+            lint_errors
+                .iter()
+                .map(lint_fingerprint)
+                .chain(link_failures.iter().map(link_fingerprint)),
+        );
+        baseline.save(path)?;
+        println!(
+            "\n{} {} problems in {}",
+            "Recorded".green(),
+            baseline.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let mut stale = Vec::new();
+    if let Some(path) = &args.baseline {
+        let mut matcher = Baseline::load(path)?.matcher();
+        lint_errors.retain(|error| !matcher.suppresses(&lint_fingerprint(error)));
+        link_failures.retain(|result| !matcher.suppresses(&link_fingerprint(result)));
+        stale = matcher.into_stale();
+    }
+
+    let formatted_errors = link_checker::format_check_results(&link_failures);
+    if !formatted_errors.is_empty() {
+        println!("\n{}", "Problematic links:".red());
+        for error in formatted_errors {
+            println!("{}", error);
+        }
     }
 
     // Print lint errors
     if !lint_errors.is_empty() {
         println!("\n{}", "Style errors:".red());
         for error in &lint_errors {
-            println!(
-                "[{}] {}:{} {}",
-                error.rule_id.yellow(),
-                error.file_path.display(),
-                error.line,
-                error.message
-            );
+            println!("{}", format_lint_error(error));
         }
-        std::process::exit(1);
     }
 
+    if !stale.is_empty() {
+        println!(
+            "\n{}",
+            "Baseline entries no longer found (run --write-baseline to drop them):".yellow()
+        );
+        for (fingerprint, count) in &stale {
+            println!("{}", format_fingerprint(fingerprint, *count));
+        }
+    }
+
+    if !lint_errors.is_empty() || !link_failures.is_empty() {
+        std::process::exit(1);
+    }
     Ok(())
 }
@@ -0,0 +1,489 @@
+use serde_json::{Map, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::config::{FrontMatterTitle, RulesConfig};
+use crate::rules::{all_rules, find_rule};
+
+/// Names of markdownlint configuration files, in the order they are looked
+/// up in each directory.
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    ".markdownlint.jsonc",
+    ".markdownlint.json",
+    ".markdownlint.yaml",
+    ".markdownlint.yml",
+];
+
+/// How many `extends` links are followed before giving up on a cycle.
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// markdownlint rules by ID and name, used to name the ones md-check does not
+/// implement.
+const MARKDOWNLINT_RULES: &[(&str, &str)] = &[
+    ("MD001", "heading-increment"),
+    ("MD003", "heading-style"),
+    ("MD004", "ul-style"),
+    ("MD005", "list-indent"),
+    ("MD007", "ul-indent"),
+    ("MD009", "no-trailing-spaces"),
+    ("MD010", "no-hard-tabs"),
+    ("MD011", "no-reversed-links"),
+    ("MD012", "no-multiple-blanks"),
+    ("MD013", "line-length"),
+    ("MD014", "commands-show-output"),
+    ("MD018", "no-missing-space-atx"),
+    ("MD019", "no-multiple-space-atx"),
+    ("MD020", "no-missing-space-closed-atx"),
+    ("MD021", "no-multiple-space-closed-atx"),
+    ("MD022", "blanks-around-headings"),
+    ("MD023", "heading-start-left"),
+    ("MD024", "no-duplicate-heading"),
+    ("MD025", "single-title"),
+    ("MD026", "no-trailing-punctuation"),
+    ("MD027", "no-multiple-space-blockquote"),
+    ("MD028", "no-blanks-blockquote"),
+    ("MD029", "ol-prefix"),
+    ("MD030", "list-marker-space"),
+    ("MD031", "blanks-around-fences"),
+    ("MD032", "blanks-around-lists"),
+    ("MD033", "no-inline-html"),
+    ("MD034", "no-bare-urls"),
+    ("MD035", "hr-style"),
+    ("MD036", "no-emphasis-as-heading"),
+    ("MD037", "no-space-in-emphasis"),
+    ("MD038", "no-space-in-code"),
+    ("MD039", "no-space-in-links"),
+    ("MD040", "fenced-code-language"),
+    ("MD041", "first-line-heading"),
+    ("MD042", "no-empty-links"),
+    ("MD043", "required-headings"),
+    ("MD044", "proper-names"),
+    ("MD045", "no-alt-text"),
+    ("MD046", "code-block-style"),
+    ("MD047", "single-trailing-newline"),
+    ("MD048", "code-fence-style"),
+    ("MD049", "emphasis-style"),
+    ("MD050", "strong-style"),
+    ("MD051", "link-fragments"),
+    ("MD052", "reference-links-images"),
+    ("MD053", "link-image-reference-definitions"),
+    ("MD054", "link-image-style"),
+    ("MD055", "table-pipe-style"),
+    ("MD056", "table-column-count"),
+    ("MD058", "blanks-around-tables"),
+    ("MD059", "descriptive-link-text"),
+];
+
+#[derive(Debug, Error)]
+pub enum MarkdownlintError {
+    #[error("failed to read markdownlint config {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("failed to parse markdownlint config {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("markdownlint config {path} extends too many files; is there a cycle?")]
+    Extends { path: PathBuf },
+}
+
+/// Settings read from a `.markdownlint.json` or `.markdownlint.yaml` file,
+/// keyed by rule ID, rule name or tag as markdownlint expects them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkdownlintConfig {
+    settings: Map<String, Value>,
+}
+
+impl MarkdownlintConfig {
+    /// Searches `start` and its ancestors for a markdownlint config file.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        start.ancestors().find_map(|dir| {
+            CONFIG_FILE_NAMES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|candidate| candidate.is_file())
+        })
+    }
+
+    /// Parses JSON (with comments) or, if `yaml` is set, YAML settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::markdownlint::MarkdownlintConfig;
+    ///
+    /// let json = r#"{
+    ///   // Allow longer lines
+    ///   "MD013": { "line_length": 120 },
+    ///   "no-inline-html": false
+    /// }"#;
+    /// let config = MarkdownlintConfig::parse(json, false).unwrap();
+    ///
+    /// let yaml = "MD013:\n  line_length: 120\nno-inline-html: false\n";
+    /// assert_eq!(config, MarkdownlintConfig::parse(yaml, true).unwrap());
+    /// ```
+    pub fn parse(content: &str, yaml: bool) -> Result<Self, String> {
+        let value: Value = if yaml {
+            serde_yaml_ng::from_str(content).map_err(|e| e.to_string())?
+        } else {
+            serde_json::from_str(&strip_json_comments(content)).map_err(|e| e.to_string())?
+        };
+        match value {
+            Value::Object(settings) => Ok(Self { settings }),
+            Value::Null => Ok(Self::default()),
+            _ => Err("expected a map of rule settings".to_string()),
+        }
+    }
+
+    /// Loads the settings from `path`, following `extends` to the files it
+    /// builds on. Settings in `path` replace those of the extended file.
+    pub fn load(path: &Path) -> Result<Self, MarkdownlintError> {
+        Self::load_nested(path, 0)
+    }
+
+    fn load_nested(path: &Path, depth: usize) -> Result<Self, MarkdownlintError> {
+        if depth > MAX_EXTENDS_DEPTH {
+            return Err(MarkdownlintError::Extends {
+                path: path.to_path_buf(),
+            });
+        }
+        let content = fs::read_to_string(path).map_err(|source| MarkdownlintError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let yaml = path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml");
+        let mut config =
+            Self::parse(&content, yaml).map_err(|message| MarkdownlintError::Parse {
+                path: path.to_path_buf(),
+                message,
+            })?;
+
+        match config.settings.remove("extends") {
+            Some(Value::String(extends)) => {
+                let base = path.parent().unwrap_or(Path::new("")).join(extends);
+                let mut settings = Self::load_nested(&base, depth + 1)?.settings;
+                settings.extend(config.settings);
+                Ok(Self { settings })
+            }
+            _ => Ok(config),
+        }
+    }
+
+    /// Applies the settings to `rules` and returns warnings about settings
+    /// md-check cannot honor.
+    ///
+    /// Rules and tags switched on or off map to `enable` and `disable`, and
+    /// `"default": false` disables every rule that has a markdownlint
+    /// counterpart. Rule options md-check supports, such as
+    /// `MD013.line_length`, map to the matching `[rules]` setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::config::RulesConfig;
+    /// use md_check::markdownlint::MarkdownlintConfig;
+    ///
+    /// let json = r#"{ "line-length": { "line_length": 80 }, "MD009": true }"#;
+    /// let mut rules = RulesConfig::default();
+    /// let warnings = MarkdownlintConfig::parse(json, false).unwrap().apply(&mut rules);
+    ///
+    /// assert_eq!(rules.line_too_long.line_length, 80);
+    /// assert_eq!(
+    ///     warnings,
+    ///     vec!["MD009 (no-trailing-spaces) is not implemented by md-check; ignoring it"]
+    /// );
+    /// ```
+    pub fn apply(&self, rules: &mut RulesConfig) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.settings.get("default") == Some(&Value::Bool(false)) {
+            for rule in all_rules(rules) {
+                if markdownlint_id(rule.id(), rule.aliases()).is_some() {
+                    rules.choose_disabled(rule.id());
+                }
+            }
+        }
+
+        for (key, value) in &self.settings {
+            if key == "default" || key == "$schema" {
+                continue;
+            }
+            let enabled = match value {
+                Value::Bool(enabled) => *enabled,
+                Value::Object(_) => true,
+                _ => {
+                    warnings.push(format!(
+                        "{key}: expected true, false or options; ignoring it"
+                    ));
+                    continue;
+                }
+            };
+
+            if let Some(rule) = find_rule(key) {
+                if enabled {
+                    rules.choose_enabled(rule.id());
+                } else {
+                    rules.choose_disabled(rule.id());
+                }
+                if let Value::Object(options) = value {
+                    for (option, value) in options {
+                        if !apply_option(rules, rule.id(), option, value) {
+                            warnings.push(format!(
+                                "{key}.{option} = {value} is not supported by md-check; ignoring it"
+                            ));
+                        }
+                    }
+                }
+            } else if is_tag(key, rules) {
+                if enabled {
+                    rules.choose_enabled(key);
+                } else {
+                    rules.choose_disabled(key);
+                }
+            } else if enabled {
+                // Disabling what md-check lacks anyway needs no warning
+                warnings.push(match known_rule(key) {
+                    Some((id, name)) => {
+                        format!("{id} ({name}) is not implemented by md-check; ignoring it")
+                    }
+                    None => format!("no md-check rule or tag matches '{key}'; ignoring it"),
+                });
+            }
+        }
+        warnings
+    }
+}
+
+/// Sets a markdownlint option of the rule `rule_id`, returning whether
+/// md-check supports it.
+fn apply_option(rules: &mut RulesConfig, rule_id: &str, option: &str, value: &Value) -> bool {
+    let length = value.as_u64().map(|n| n as usize);
+    let line_too_long = &mut rules.line_too_long;
+    match (rule_id, option) {
+        ("LINE_TOO_LONG", "line_length") => set(&mut line_too_long.line_length, length),
+        ("LINE_TOO_LONG", "code_block_line_length") => {
+            set(&mut line_too_long.code_block_line_length, length.map(Some))
+        }
+        ("LINE_TOO_LONG", "heading_line_length") => {
+            set(&mut line_too_long.heading_line_length, length.map(Some))
+        }
+        ("LINE_TOO_LONG", "code_blocks") => set(&mut line_too_long.code_blocks, value.as_bool()),
+        ("LINE_TOO_LONG", "headings" | "headers") => {
+            set(&mut line_too_long.headings, value.as_bool())
+        }
+        ("LINE_TOO_LONG", "tables") => set(&mut line_too_long.tables, value.as_bool()),
+        ("LINE_TOO_LONG", "strict") => set(&mut line_too_long.strict, value.as_bool()),
+        ("NO_HTML", "allowed_elements") => set(
+            &mut rules.no_html.allowed_elements,
+            serde_json::from_value(value.clone()).ok(),
+        ),
+        ("MD003", "style") => set(
+            &mut rules.md003.style,
+            serde_json::from_value(value.clone()).ok(),
+        ),
+        ("MD004", "style") => set(
+            &mut rules.md004.style,
+            serde_json::from_value(value.clone()).ok(),
+        ),
+        ("MD007", "indent") => set(&mut rules.md007.indent, length),
+        ("MD029", "style") => set(
+            &mut rules.md029.style,
+            serde_json::from_value(value.clone()).ok(),
+        ),
+        ("MD030", "ul_single") => set(&mut rules.md030.ul_single, length),
+        ("MD030", "ol_single") => set(&mut rules.md030.ol_single, length),
+        ("MD030", "ul_multi") => set(&mut rules.md030.ul_multi, length),
+        ("MD030", "ol_multi") => set(&mut rules.md030.ol_multi, length),
+        ("MD024", "siblings_only") => set(&mut rules.md024.siblings_only, value.as_bool()),
+        ("MD026", "punctuation") => set(
+            &mut rules.md026.punctuation,
+            value.as_str().map(str::to_string),
+        ),
+        ("MD025", "level") => set(&mut rules.md025.level, heading_level(value)),
+        ("MD041", "level") => set(&mut rules.md041.level, heading_level(value)),
+        // Only turning front matter titles off is supported, not other patterns
+        ("MD025", "front_matter_title") if value == "" => {
+            rules.md025.front_matter_title = FrontMatterTitle::Ignore;
+            true
+        }
+        ("MD041", "front_matter_title") if value == "" => {
+            rules.md041.front_matter_title = false;
+            true
+        }
+        _ => false,
+    }
+}
+
+fn heading_level(value: &Value) -> Option<u8> {
+    value
+        .as_u64()
+        .filter(|level| (1..=6).contains(level))
+        .map(|level| level as u8)
+}
+
+/// Stores `value` in `target` if it has the right type.
+fn set<T>(target: &mut T, value: Option<T>) -> bool {
+    match value {
+        Some(value) => {
+            *target = value;
+            true
+        }
+        None => false,
+    }
+}
+
+/// The markdownlint rule ID among a rule's ID and aliases, if it has one.
+fn markdownlint_id<'a>(id: &'a str, aliases: &[&'a str]) -> Option<&'a str> {
+    std::iter::once(id)
+        .chain(aliases.iter().copied())
+        .find(|name| MARKDOWNLINT_RULES.iter().any(|(md, _)| md == name))
+}
+
+fn is_tag(name: &str, rules: &RulesConfig) -> bool {
+    all_rules(rules)
+        .iter()
+        .any(|rule| rule.tags().iter().any(|tag| tag.eq_ignore_ascii_case(name)))
+}
+
+/// Looks up a markdownlint rule by ID or name, ignoring case.
+fn known_rule(name: &str) -> Option<(&'static str, &'static str)> {
+    MARKDOWNLINT_RULES
+        .iter()
+        .find(|(id, rule_name)| {
+            id.eq_ignore_ascii_case(name) || rule_name.eq_ignore_ascii_case(name)
+        })
+        .copied()
+}
+
+/// Blanks out `//` and `/* */` comments outside of strings, as allowed in
+/// markdownlint's JSON files.
+fn strip_json_comments(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                // Keep the newline so that error positions stay right
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        output.push('\n');
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{HeadingStyleOption, OrderedListStyle};
+    use crate::rules::get_rules_with_config;
+    use tempfile::TempDir;
+
+    fn enabled_ids(rules: &RulesConfig) -> Vec<&'static str> {
+        get_rules_with_config(rules)
+            .iter()
+            .map(|rule| rule.id())
+            .collect()
+    }
+
+    #[test]
+    fn test_default_false_keeps_md_check_only_rules() {
+        let config =
+            MarkdownlintConfig::parse("default: false\nMD042: true\nwhitespace: false\n", true)
+                .unwrap();
+        let mut rules = RulesConfig::default();
+
+        assert!(config.apply(&mut rules).is_empty());
+        assert_eq!(
+            enabled_ids(&rules),
+            vec!["NO_EMPTY_LINKS", "UNSAFE_HTML", "URL_HYGIENE"]
+        );
+    }
+
+    #[test]
+    fn test_unimplemented_rules_warn_only_when_enabled() {
+        let json = r#"{"MD010": false, "no-bare-urls": true, "single-h1": {"level": 2, "front_matter_title": ""}, "MD033": {"allowed_elements": ["br"], "table_allowed_elements": ["br"]}, "heading-style": {"style": "setext_with_atx"}, "MD026": {"punctuation": ".,"}, "MD024": {"siblings_only": true}, "ul-indent": {"indent": 4}, "MD029": {"style": "ordered"}}"#;
+        let mut rules = RulesConfig::default();
+        let warnings = MarkdownlintConfig::parse(json, false)
+            .unwrap()
+            .apply(&mut rules);
+
+        assert_eq!(rules.no_html.allowed_elements, vec!["br"]);
+        assert_eq!(rules.md025.level, 2);
+        assert_eq!(rules.md025.front_matter_title, FrontMatterTitle::Ignore);
+        assert_eq!(rules.md003.style, HeadingStyleOption::SetextWithAtx);
+        assert_eq!(rules.md026.punctuation, ".,");
+        assert!(rules.md024.siblings_only);
+        assert_eq!(rules.md007.indent, 4);
+        assert_eq!(rules.md029.style, OrderedListStyle::Ordered);
+        assert_eq!(
+            warnings,
+            vec![
+                "MD033.table_allowed_elements = [\"br\"] is not supported by md-check; ignoring it",
+                "MD034 (no-bare-urls) is not implemented by md-check; ignoring it",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_follows_extends() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("base.json"),
+            r#"{"MD013": {"line_length": 80, "code_blocks": false}, "MD042": false}"#,
+        )
+        .unwrap();
+        let path = dir.path().join(".markdownlint.jsonc");
+        fs::write(
+            &path,
+            "{\n  \"extends\": \"base.json\", /* shared */\n  \"MD042\": true // \"quoted\"\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(MarkdownlintConfig::discover(dir.path()), Some(path.clone()));
+        let mut rules = RulesConfig::default();
+        MarkdownlintConfig::load(&path).unwrap().apply(&mut rules);
+
+        assert_eq!(rules.line_too_long.line_length, 80);
+        assert!(!rules.line_too_long.code_blocks);
+        assert_eq!(rules.enable, vec!["LINE_TOO_LONG", "NO_EMPTY_LINKS"]);
+        assert!(rules.disable.is_empty());
+    }
+
+    #[test]
+    fn test_strip_json_comments_keeps_strings() {
+        assert_eq!(
+            strip_json_comments("{\"a\": \"http://x/*y*/\" /* c */} // d"),
+            "{\"a\": \"http://x/*y*/\" } "
+        );
+    }
+}
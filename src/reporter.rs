@@ -0,0 +1,255 @@
+use colored::*;
+
+use crate::link_checker::{CheckResult, LinkInfo, ResultOrigin};
+
+/// Position of a finished link within the current run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub completed: usize,
+    /// Number of links in the run, once all of them have been queued.
+    pub total: Option<usize>,
+}
+
+/// Counts of link results by outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub total: usize,
+    pub successful: usize,
+    pub redirects: usize,
+    pub failed: usize,
+    pub cached: usize,
+    pub unchecked: usize,
+}
+
+impl Summary {
+    /// Tallies the given results. Unchecked links count towards `total` and
+    /// `unchecked` only.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use reqwest::StatusCode;
+    /// use md_check::link_checker::{CheckResult, LinkInfo, ResultOrigin};
+    /// use md_check::reporter::Summary;
+    ///
+    /// let link = LinkInfo {
+    ///     url: "https://example.com".to_string(),
+    ///     file_path: PathBuf::from("doc.md"),
+    ///     line: 1,
+    /// };
+    /// let results = vec![CheckResult {
+    ///     link,
+    ///     status: StatusCode::NOT_FOUND,
+    ///     error_message: None,
+    ///     origin: ResultOrigin::Cache,
+    /// }];
+    ///
+    /// let summary = Summary::from_results(&results);
+    ///
+    /// assert_eq!(summary.failed, 1);
+    /// assert_eq!(summary.cached, 1);
+    /// ```
+    pub fn from_results(results: &[CheckResult]) -> Self {
+        let mut summary = Self {
+            total: results.len(),
+            ..Self::default()
+        };
+
+        for result in results {
+            match result.origin {
+                ResultOrigin::Unchecked => {
+                    summary.unchecked += 1;
+                    continue;
+                }
+                ResultOrigin::Cache => summary.cached += 1,
+                ResultOrigin::Network | ResultOrigin::Local => {}
+            }
+
+            if result.status.is_success() {
+                summary.successful += 1;
+            } else if result.status.is_redirection() {
+                summary.redirects += 1;
+            } else if result.status.is_client_error() || result.status.is_server_error() {
+                summary.failed += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// Receives progress events from a [`crate::link_checker::LinkChecker`].
+///
+/// Every method has an empty default, so implementations only override the
+/// events they care about. Events for different links may arrive from
+/// concurrent requests, but never at the same time.
+pub trait Reporter: Send + Sync {
+    /// `link` has been queued for checking.
+    fn started(&self, _link: &LinkInfo) {}
+
+    /// A request for `link` failed and will be retried.
+    fn retried(&self, _link: &LinkInfo, _attempt: u32, _error: &str) {}
+
+    /// The result for one link is available.
+    fn finished(&self, _result: &CheckResult, _progress: Progress) {}
+
+    /// All links have been checked.
+    fn summary(&self, _summary: &Summary) {}
+}
+
+/// Discards every event. Used when no reporter is configured.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopReporter;
+
+impl Reporter for NoopReporter {}
+
+/// Prints colored progress to stdout, or GitHub Actions workflow commands
+/// when running inside a workflow.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsoleReporter {
+    github_actions: bool,
+}
+
+impl Default for ConsoleReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsoleReporter {
+    /// Creates a reporter that emits workflow commands if `GITHUB_ACTIONS` is set.
+    pub fn new() -> Self {
+        Self::with_github_actions(std::env::var("GITHUB_ACTIONS").is_ok())
+    }
+
+    pub fn with_github_actions(github_actions: bool) -> Self {
+        Self { github_actions }
+    }
+}
+
+impl Reporter for ConsoleReporter {
+    fn started(&self, link: &LinkInfo) {
+        if self.github_actions {
+            println!(
+                "::debug::Checking link: {} in {}",
+                link.url,
+                link.file_path.display()
+            );
+        }
+    }
+
+    fn retried(&self, link: &LinkInfo, attempt: u32, error: &str) {
+        if self.github_actions {
+            println!(
+                "::debug::Retrying link {} (attempt {}): {}",
+                link.url, attempt, error
+            );
+        }
+    }
+
+    fn finished(&self, result: &CheckResult, progress: Progress) {
+        let link = &result.link;
+        let position = match progress.total {
+            Some(total) => format!("[{}/{}]", progress.completed, total),
+            None => format!("[{}]", progress.completed),
+        };
+
+        if self.github_actions {
+            let cached = if result.origin == ResultOrigin::Cache {
+                " (cached)"
+            } else {
+                ""
+            };
+            if result.origin == ResultOrigin::Unchecked {
+                println!("::debug::Link {} not checked (offline)", link.url);
+            } else if result.status.is_success() {
+                println!(
+                    "::debug::Link {} status: {} (success){}",
+                    link.url, result.status, cached
+                );
+            } else {
+                println!(
+                    "::error file={}::Link {} failed with status {}{}{}",
+                    link.file_path.display(),
+                    link.url,
+                    result.status,
+                    result
+                        .error_message
+                        .as_ref()
+                        .map(|m| format!(" - {}", m))
+                        .unwrap_or_default(),
+                    cached
+                );
+            }
+            return;
+        }
+
+        match result.origin {
+            ResultOrigin::Unchecked => {
+                println!("{} {} - {}", position, "UNCHECKED".yellow(), link.url)
+            }
+            ResultOrigin::Cache => println!(
+                "{} {} - {}",
+                position,
+                if result.status.is_success() {
+                    "GOOD (cached)".green()
+                } else {
+                    "FAIL (cached)".red()
+                },
+                link.url
+            ),
+            ResultOrigin::Network | ResultOrigin::Local => {
+                let status_str = match result.status.as_u16() {
+                    200..=299 => result.status.to_string().green(),
+                    300..=399 => result.status.to_string().yellow(),
+                    400..=499 => result.status.to_string().red(),
+                    _ => result.status.to_string().red().bold(),
+                };
+                println!(
+                    "{} {} - {} - {}",
+                    position,
+                    status_str,
+                    if result.status.is_success() {
+                        "GOOD".green()
+                    } else {
+                        "FAIL".red()
+                    },
+                    link.url
+                );
+            }
+        }
+    }
+
+    fn summary(&self, summary: &Summary) {
+        // Nothing was checked, so there is nothing to report
+        if summary.total == 0 {
+            return;
+        }
+
+        if self.github_actions {
+            println!("::notice::Checked {} links", summary.total);
+            println!("::group::Summary");
+        }
+
+        println!("\n{}", "Link check completed.".bold());
+        println!("\n{}", "Summary:".bold());
+        println!("{}: {}", "Total".bold(), summary.total);
+        println!("{}: {}", "Successful".green(), summary.successful);
+        if summary.redirects > 0 {
+            println!("{}: {}", "Redirects".yellow(), summary.redirects);
+        }
+        if summary.failed > 0 {
+            println!("{}: {}", "Failed".red(), summary.failed);
+        }
+        if summary.cached > 0 {
+            println!("{}: {}", "From cache".cyan(), summary.cached);
+        }
+        if summary.unchecked > 0 {
+            println!("{}: {}", "Unchecked".yellow(), summary.unchecked);
+        }
+
+        if self.github_actions {
+            println!("::endgroup::");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct BlanksAroundHeadingsRule;
+
+impl Default for BlanksAroundHeadingsRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlanksAroundHeadingsRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let kinds = classify_lines(content);
+        let is_blank = |idx: usize| lines[idx].trim().is_empty();
+
+        let mut problems = Vec::new();
+        for heading in headings::source_headings(content) {
+            if !heading.top_level {
+                continue;
+            }
+            let (first, last) = (heading.first_line, heading.last_line);
+            // Front matter is not rendered, so nothing needs separating from it
+            if first > 0 && !is_blank(first - 1) && kinds[first - 1] != LineKind::FrontMatter {
+                problems.push(SourceProblem {
+                    line: first,
+                    column: 1,
+                    message:
+                        "Headings should be surrounded by blank lines: missing blank line above"
+                            .to_string(),
+                    lines: first - 1..first,
+                    replacement: vec![lines[first - 1].to_string(), String::new()],
+                });
+            }
+            if last + 1 < lines.len() && !is_blank(last + 1) {
+                problems.push(SourceProblem {
+                    line: first,
+                    column: 1,
+                    message:
+                        "Headings should be surrounded by blank lines: missing blank line below"
+                            .to_string(),
+                    lines: last..last + 1,
+                    replacement: vec![lines[last].to_string(), String::new()],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for BlanksAroundHeadingsRule {
+    fn id(&self) -> &'static str {
+        "MD022"
+    }
+
+    fn name(&self) -> &'static str {
+        "Blanks Around Headings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures headings are surrounded by blank lines."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["blanks-around-headings"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings without a blank line above or below them. Some parsers do
+not recognize a heading that directly follows a paragraph, and blank lines
+make the structure of the source easy to see. The start and end of the
+document and the end of front matter count as blank.
+
+`md-check fix` inserts the missing blank lines.
+
+Bad:
+
+```markdown
+# Title
+Some text.
+## Usage
+```
+
+Good:
+
+```markdown
+# Title
+
+Some text.
+
+## Usage
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_missing_blank_lines() {
+        let content = "---\ntitle: x\n---\n# Title\nText\nUsage\n---\n## Details\n";
+        let errors: Vec<_> = problems(content, "MD022")
+            .into_iter()
+            .map(|(line, _, message)| (line, message.rsplit(' ').next().unwrap().to_string()))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                (4, "below".to_string()),
+                (5, "above".to_string()),
+                (5, "below".to_string()),
+                (8, "above".to_string()),
+            ]
+        );
+        assert_eq!(
+            BlanksAroundHeadingsRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "---\ntitle: x\n---\n# Title\n\nText\nUsage\n---\n\n## Details\n"
+        );
+    }
+}
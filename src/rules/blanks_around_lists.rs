@@ -0,0 +1,160 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct BlanksAroundListsRule;
+
+impl Default for BlanksAroundListsRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlanksAroundListsRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let kinds = classify_lines(content);
+        let is_blank = |idx: usize| lines[idx].trim().is_empty();
+
+        let mut problems = Vec::new();
+        for list in lists::source_lists(content) {
+            if !list.parents.is_empty() || list.quoted {
+                continue;
+            }
+            let (first, last) = (list.first_line, list.last_line);
+            let column = list
+                .items
+                .first()
+                .map_or(1, |item| fixes::column_at(lines[item.line], item.offset));
+            // Front matter is not rendered, so nothing needs separating from it
+            if first > 0 && !is_blank(first - 1) && kinds[first - 1] != LineKind::FrontMatter {
+                problems.push(SourceProblem {
+                    line: first,
+                    column,
+                    message: "Lists should be surrounded by blank lines: missing blank line above"
+                        .to_string(),
+                    lines: first - 1..first,
+                    replacement: vec![lines[first - 1].to_string(), String::new()],
+                });
+            }
+            if last + 1 < lines.len() && !is_blank(last + 1) {
+                problems.push(SourceProblem {
+                    line: last,
+                    column: 1,
+                    message: "Lists should be surrounded by blank lines: missing blank line below"
+                        .to_string(),
+                    lines: last..last + 1,
+                    replacement: vec![lines[last].to_string(), String::new()],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for BlanksAroundListsRule {
+    fn id(&self) -> &'static str {
+        "MD032"
+    }
+
+    fn name(&self) -> &'static str {
+        "Blanks Around Lists"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures lists are surrounded by blank lines."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["blanks-around-lists"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports top-level lists without a blank line above or below them. Some
+parsers do not start a list in the middle of a paragraph, and a line right
+after a list may be read as part of its last item. The start and end of the
+document and the end of front matter count as blank.
+
+`md-check fix` inserts the missing blank lines.
+
+Bad:
+
+```markdown
+Steps:
+- Install
+- Configure
+```
+
+Good:
+
+```markdown
+Steps:
+
+- Install
+- Configure
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_missing_blank_lines() {
+        let content = "Steps:\n - a\n - b\n```\ncode\n```\n\n- c\n\n  d\n";
+
+        assert_eq!(
+            problems(content, "MD032"),
+            vec![
+                (
+                    2,
+                    Some(2),
+                    "Lists should be surrounded by blank lines: missing blank line above"
+                        .to_string()
+                ),
+                (
+                    3,
+                    Some(1),
+                    "Lists should be surrounded by blank lines: missing blank line below"
+                        .to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            BlanksAroundListsRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "Steps:\n\n - a\n - b\n\n```\ncode\n```\n\n- c\n\n  d\n"
+        );
+    }
+}
@@ -1,25 +1,173 @@
-use pulldown_cmark::Event;
-use std::path::PathBuf;
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+use crate::suppression::Suppressions;
+use crate::text::{LineEnding, LineIndex, lines_with_endings};
+
+/// Where the documentation of each rule is published, one section per rule.
+pub const DOCS_URL: &str = "https://github.com/eugenescodes/md-check/blob/main/docs/rules.md";
+
+/// Tags that group related rules, so they can be enabled or disabled together.
+pub mod tags {
+    pub const WHITESPACE: &str = "whitespace";
+    pub const LINKS: &str = "links";
+    pub const HEADINGS: &str = "headings";
+    pub const LISTS: &str = "lists";
+    pub const HTML: &str = "html";
+    pub const SECURITY: &str = "security";
+    pub const ACCESSIBILITY: &str = "accessibility";
+    pub const LINE_LENGTH: &str = "line_length";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LintError {
     pub file_path: PathBuf,
     pub line: usize,
+    /// 1-based column in characters, when the rule can point at one.
+    #[serde(default)]
+    pub column: Option<usize>,
     pub message: String,
     pub rule_id: String,
 }
 
+/// The kind of Markdown block a line belongs to, for rules that treat
+/// some blocks differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineKind {
+    /// Paragraphs, lists, blank lines and everything else.
+    #[default]
+    Text,
+    /// Inside a fenced or indented code block, fences included.
+    Code,
+    Heading,
+    Table,
+    /// Inside an HTML block.
+    Html,
+    /// Inside the front matter at the start of the document, delimiters included.
+    FrontMatter,
+}
+
+/// Markdown extensions the rules see. Front matter is parsed as metadata
+/// rather than as a thematic break and a heading.
+pub const PARSER_OPTIONS: Options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+    .union(Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
+
+/// Finds the kind of block each line of `content` belongs to, indexed by
+/// 0-based line number. Tables are recognized here even though the rules
+/// themselves see them as plain paragraphs.
+pub fn classify_lines(content: &str) -> Vec<LineKind> {
+    let line_index = LineIndex::new(content);
+    let mut kinds = vec![LineKind::Text; lines_with_endings(content).count()];
+    for (event, range) in
+        Parser::new_ext(content, PARSER_OPTIONS.union(Options::ENABLE_TABLES)).into_offset_iter()
+    {
+        let kind = match event {
+            Event::Start(Tag::CodeBlock(_)) => LineKind::Code,
+            Event::Start(Tag::Heading { .. }) => LineKind::Heading,
+            Event::Start(Tag::Table(_)) => LineKind::Table,
+            Event::Start(Tag::HtmlBlock) => LineKind::Html,
+            Event::Start(Tag::MetadataBlock(_)) => LineKind::FrontMatter,
+            _ => continue,
+        };
+        // The range may end on the following line terminator
+        let first = line_index.line(range.start);
+        let last = line_index.line(range.end.saturating_sub(1).max(range.start));
+        for line in first..=last {
+            if let Some(slot) = kinds.get_mut(line - 1) {
+                *slot = kind;
+            }
+        }
+    }
+    kinds
+}
+
 pub struct LintContext {
     pub file_path: PathBuf,
     pub current_line_number: usize,
     pub current_line_is_blank: bool,
     pub previous_line_was_blank: bool,
     pub line_text: String,
+    /// Block the current line belongs to; always `Text` for AST rules.
+    pub line_kind: LineKind,
+    /// Terminator of the current line; `None` on a final line without one.
+    pub line_ending: Option<LineEnding>,
+    /// Terminator of the first line that has one, if any.
+    pub first_line_ending: Option<LineEnding>,
 }
 
 pub trait Rule {
     fn id(&self) -> &'static str;
     fn name(&self) -> &'static str;
     fn description(&self) -> &'static str;
-    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError>;
+
+    /// Checks one parser event in the context of its line. Rules that only
+    /// look at the whole document leave this out and implement
+    /// [`Rule::check_document`] instead.
+    fn check(&self, _event: &Event<'_>, _context: &LintContext) -> Option<LintError> {
+        None
+    }
+
+    /// Checks the whole document at once, for rules that compare several
+    /// lines. Called once per document, after [`Rule::check`]; the returned
+    /// errors carry their own line and column.
+    fn check_document(&self, _content: &str, _file_path: &Path) -> Vec<LintError> {
+        Vec::new()
+    }
+
+    /// Long-form documentation in Markdown, shown by `md-check explain`,
+    /// with examples of what the rule reports and how to correct it.
+    fn explanation(&self) -> &'static str {
+        self.description()
+    }
+
+    /// Whether the rule runs when the configuration does not mention it.
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+
+    /// Tags from [`tags`] that the rule belongs to.
+    fn tags(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Other names the rule answers to in config files, on the command line
+    /// and in suppression comments, such as its markdownlint ID and name.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether [`Rule::fix`] can correct the rule's violations.
+    fn fixable(&self) -> bool {
+        false
+    }
+
+    /// Stable link to the rule's section in the published documentation.
+    fn documentation_url(&self) -> String {
+        format!("{}#{}", DOCS_URL, self.id().to_lowercase())
+    }
+
+    /// Whether `name` is the rule's ID or one of its aliases, ignoring case.
+    fn is_named(&self, name: &str) -> bool {
+        self.id().eq_ignore_ascii_case(name)
+            || self
+                .aliases()
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether suppression comments silence the rule, by ID or alias, on
+    /// the 1-based `line`.
+    fn is_suppressed(&self, suppressions: &Suppressions, line: usize) -> bool {
+        std::iter::once(self.id())
+            .chain(self.aliases().iter().copied())
+            .any(|name| suppressions.is_suppressed(name, line))
+    }
+
+    /// Returns `content` with this rule's violations corrected, or `None`
+    /// if the rule cannot fix them automatically. Violations on lines where
+    /// `suppressions` silence the rule are left as they are.
+    fn fix(&self, _content: &str, _suppressions: &Suppressions) -> Option<String> {
+        None
+    }
 }
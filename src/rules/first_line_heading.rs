@@ -0,0 +1,159 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use super::headings::FrontMatter;
+use super::html::{self, HtmlState};
+use crate::config::FirstLineHeadingConfig;
+use pulldown_cmark::{Event, Tag};
+use std::cell::Cell;
+
+pub struct FirstLineHeadingRule {
+    config: FirstLineHeadingConfig,
+    front_matter: FrontMatter,
+    /// Whether the first block of the document was checked.
+    done: Cell<bool>,
+}
+
+impl Default for FirstLineHeadingRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FirstLineHeadingRule {
+    pub fn new() -> Self {
+        Self::with_config(FirstLineHeadingConfig::default())
+    }
+
+    pub fn with_config(config: FirstLineHeadingConfig) -> Self {
+        Self {
+            config,
+            front_matter: FrontMatter::default(),
+            done: Cell::new(false),
+        }
+    }
+
+    /// Whether the first block of the document, starting with `event`, is
+    /// acceptable, or `None` if `event` does not start one.
+    fn first_block_ok(&self, event: &Event<'_>) -> Option<bool> {
+        let level = self.config.level as usize;
+        match event {
+            Event::Start(Tag::HtmlBlock) | Event::End(_) => None,
+            Event::Html(text) => {
+                // Comments, such as suppression comments, may come first
+                let scan = html::scan(text, HtmlState::Text);
+                let tag = scan.tags.first()?;
+                Some(tag.name == format!("h{}", level))
+            }
+            Event::Start(Tag::Heading { level: found, .. }) => Some(*found as usize == level),
+            _ => Some(false),
+        }
+    }
+}
+
+impl Rule for FirstLineHeadingRule {
+    fn id(&self) -> &'static str {
+        "MD041"
+    }
+
+    fn name(&self) -> &'static str {
+        "First Line Heading"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures a document starts with a top-level heading."
+    }
+
+    // Many documents, such as snippets included elsewhere, have no title
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["first-line-heading", "first-line-h1"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports documents whose first block is not a top-level heading, which
+serves as the document's title. Front matter and HTML comments before the
+heading are skipped, and an HTML `<h1>` counts as well. The level is set with
+`level` under `[rules.md041]`.
+
+A document without the heading is accepted if its front matter has a
+`title`, unless `front_matter_title = false`. The rule is off by default;
+enable it with `enable = ["MD041"]`.
+
+Bad:
+
+```markdown
+This guide explains the setup.
+
+# Setup
+```
+
+Good:
+
+```markdown
+# Setup
+
+This guide explains the setup.
+```"#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        if self.done.get() || self.front_matter.observe(event) {
+            return None;
+        }
+        let ok = self.first_block_ok(event)?;
+        self.done.set(true);
+        if ok || (self.config.front_matter_title && self.front_matter.title().is_some()) {
+            return None;
+        }
+        Some(LintError {
+            file_path: context.file_path.to_path_buf(),
+            line: 0,
+            column: None,
+            message: format!(
+                "Document should start with a level {} heading",
+                self.config.level
+            ),
+            rule_id: self.id().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::RulesConfig;
+    use crate::linter::lint_with_config;
+    use std::path::Path;
+
+    fn lines(content: &str) -> Vec<usize> {
+        let rules = RulesConfig {
+            enable: vec!["first-line-heading".to_string()],
+            ..RulesConfig::default()
+        };
+        lint_with_config(content, Path::new("test.md"), &rules)
+            .into_iter()
+            .filter(|e| e.rule_id == "MD041")
+            .map(|e| e.line)
+            .collect()
+    }
+
+    #[test]
+    fn test_first_block() {
+        assert!(lines("<!-- markdownlint-disable MD033 -->\n\n# Title\n").is_empty());
+        assert!(lines("<h1 align=\"center\">Title</h1>\n\nText\n").is_empty());
+        assert!(lines("").is_empty());
+        assert_eq!(lines("Intro\n\n# Title\n"), vec![1]);
+        assert_eq!(lines("<!-- note -->\n## Title\n"), vec![2]);
+    }
+
+    #[test]
+    fn test_front_matter() {
+        assert!(lines("---\ntitle: Setup\n---\nIntro\n").is_empty());
+        assert_eq!(lines("+++\ndraft = true\n+++\nIntro\n"), vec![4]);
+    }
+}
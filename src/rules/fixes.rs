@@ -0,0 +1,91 @@
+//! Problems found by rules that read the source line by line, and the
+//! edits that fix them.
+
+use super::common::{LintError, Rule};
+use crate::suppression::Suppressions;
+use crate::text::{LineEnding, lines_with_endings};
+use std::ops::Range;
+use std::path::Path;
+
+/// A problem found in the source, with the edit that corrects it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceProblem {
+    /// 0-based index of the line the problem is reported on.
+    pub line: usize,
+    /// 1-based character column.
+    pub column: usize,
+    pub message: String,
+    /// Lines replaced by the fix, by 0-based index.
+    pub lines: Range<usize>,
+    /// New lines, without terminators.
+    pub replacement: Vec<String>,
+}
+
+/// Turns `problems` into lint errors of the rule `rule_id`.
+pub fn report(rule_id: &str, file_path: &Path, problems: Vec<SourceProblem>) -> Vec<LintError> {
+    problems
+        .into_iter()
+        .map(|problem| LintError {
+            file_path: file_path.to_path_buf(),
+            line: problem.line + 1,
+            column: Some(problem.column),
+            message: problem.message,
+            rule_id: rule_id.to_string(),
+        })
+        .collect()
+}
+
+/// Applies the edits of `problems` found by `rule` in line order, except on
+/// lines where `suppressions` silence the rule. An edit that overlaps an
+/// earlier one is skipped.
+pub fn apply(
+    rule: &dyn Rule,
+    content: &str,
+    suppressions: &Suppressions,
+    mut problems: Vec<SourceProblem>,
+) -> String {
+    problems.retain(|problem| !rule.is_suppressed(suppressions, problem.line + 1));
+    problems.sort_by_key(|problem| problem.lines.start);
+    let lines: Vec<_> = lines_with_endings(content).collect();
+    let line_ending = lines
+        .iter()
+        .find_map(|(_, ending)| LineEnding::from_terminator(ending))
+        .unwrap_or(LineEnding::Lf)
+        .as_str();
+    let mut fixed = String::with_capacity(content.len());
+    let mut next = 0;
+    for problem in problems {
+        if problem.lines.start < next || problem.lines.end > lines.len() {
+            continue;
+        }
+        for (line, ending) in &lines[next..problem.lines.start] {
+            fixed.push_str(line);
+            fixed.push_str(ending);
+        }
+        // The last new line keeps the terminator of the last replaced one
+        let last_ending = match problem.lines.end.checked_sub(1) {
+            Some(last) if last >= problem.lines.start => lines[last].1,
+            _ => line_ending,
+        };
+        let count = problem.replacement.len();
+        for (idx, line) in problem.replacement.iter().enumerate() {
+            fixed.push_str(line);
+            fixed.push_str(if idx + 1 == count {
+                last_ending
+            } else {
+                line_ending
+            });
+        }
+        next = problem.lines.end;
+    }
+    for (line, ending) in &lines[next..] {
+        fixed.push_str(line);
+        fixed.push_str(ending);
+    }
+    fixed
+}
+
+/// 1-based character column of the byte `offset` in `line`.
+pub fn column_at(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
@@ -0,0 +1,118 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use pulldown_cmark::{Event, Tag};
+use std::cell::Cell;
+
+pub struct HeadingIncrementRule {
+    /// Level of the last heading seen.
+    previous: Cell<Option<usize>>,
+}
+
+impl Default for HeadingIncrementRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadingIncrementRule {
+    pub fn new() -> Self {
+        Self {
+            previous: Cell::new(None),
+        }
+    }
+}
+
+impl Rule for HeadingIncrementRule {
+    fn id(&self) -> &'static str {
+        "MD001"
+    }
+
+    fn name(&self) -> &'static str {
+        "Heading Increment"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures heading levels only increase by one level at a time."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["heading-increment"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings that are more than one level deeper than the heading before
+them, such as an `###` heading right after a `#` heading. Skipped levels
+break the document outline that tables of contents and screen readers
+rely on. Going back up any number of levels is fine.
+
+Bad:
+
+```markdown
+# Title
+
+### Details
+```
+
+Good:
+
+```markdown
+# Title
+
+## Details
+```"#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        let Event::Start(Tag::Heading { level, .. }) = event else {
+            return None;
+        };
+        let level = *level as usize;
+        match self.previous.replace(Some(level)) {
+            Some(previous) if level > previous + 1 => Some(LintError {
+                file_path: context.file_path.to_path_buf(),
+                line: 0,
+                column: None,
+                message: format!(
+                    "Heading level should increase by one: expected h{}, found h{}",
+                    previous + 1,
+                    level
+                ),
+                rule_id: self.id().to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linter::lint;
+    use std::path::Path;
+
+    #[test]
+    fn test_skipped_levels() {
+        let content = "# A\n\n### B\n\n## C\n\n#### D\n\n# E\n\n## F\n";
+        let errors: Vec<_> = lint(content, Path::new("test.md"))
+            .into_iter()
+            .filter(|e| e.rule_id == "MD001")
+            .map(|e| (e.line, e.message))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    3,
+                    "Heading level should increase by one: expected h2, found h3".to_string()
+                ),
+                (
+                    7,
+                    "Heading level should increase by one: expected h3, found h4".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct HeadingStartLeftRule;
+
+impl Default for HeadingStartLeftRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadingStartLeftRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        headings::source_headings(content)
+            .into_iter()
+            .filter(|heading| heading.top_level)
+            .filter(|heading| lines[heading.first_line].starts_with([' ', '\t']))
+            .map(|heading| SourceProblem {
+                line: heading.first_line,
+                column: 1,
+                message: "Headings must start at the beginning of the line".to_string(),
+                lines: heading.first_line..heading.last_line + 1,
+                replacement: lines[heading.first_line..=heading.last_line]
+                    .iter()
+                    .map(|line| line.trim_start().to_string())
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl Rule for HeadingStartLeftRule {
+    fn id(&self) -> &'static str {
+        "MD023"
+    }
+
+    fn name(&self) -> &'static str {
+        "Heading Start Left"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures headings start at the beginning of the line."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["heading-start-left"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings indented by one to three spaces. They still render as
+headings, but the indentation suggests they belong to a list item or a code
+block, and four spaces turn them into code. Headings inside lists and block
+quotes are not affected.
+
+`md-check fix` removes the indentation.
+
+Bad:
+
+```markdown
+  # Title
+```
+
+Good:
+
+```markdown
+# Title
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_indented_headings() {
+        let content = "  # Title\n\n Usage\n ---\n\n- Item\n\n  ## Nested\n\n    # Code\n";
+
+        assert_eq!(
+            positions(content, "MD023"),
+            vec![(1, Some(1)), (3, Some(1))]
+        );
+        assert_eq!(
+            HeadingStartLeftRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "# Title\n\nUsage\n---\n\n- Item\n\n  ## Nested\n\n    # Code\n"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::{self, AtxLine, HeadingStyle, SourceHeading};
+use crate::config::{HeadingStyleConfig, HeadingStyleOption};
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+pub struct HeadingStyleRule {
+    config: HeadingStyleConfig,
+}
+
+impl Default for HeadingStyleRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadingStyleRule {
+    pub fn new() -> Self {
+        Self::with_config(HeadingStyleConfig::default())
+    }
+
+    pub fn with_config(config: HeadingStyleConfig) -> Self {
+        Self { config }
+    }
+
+    /// Style `heading` should have, given the style of the first heading and
+    /// whether the heading has any text. `None` allows any style.
+    fn expected(
+        &self,
+        heading: &SourceHeading,
+        first: HeadingStyle,
+        empty: bool,
+    ) -> Option<HeadingStyle> {
+        // Setext headings only exist for levels 1 and 2, and need text
+        let deep = heading.level > 2 || empty;
+        let style = match self.config.style {
+            HeadingStyleOption::Consistent if first == HeadingStyle::Setext && deep => {
+                HeadingStyle::Atx
+            }
+            HeadingStyleOption::Consistent => first,
+            HeadingStyleOption::Atx => HeadingStyle::Atx,
+            HeadingStyleOption::AtxClosed => HeadingStyle::AtxClosed,
+            HeadingStyleOption::Setext if deep => return None,
+            HeadingStyleOption::SetextWithAtx if deep => HeadingStyle::Atx,
+            HeadingStyleOption::SetextWithAtxClosed if deep => HeadingStyle::AtxClosed,
+            HeadingStyleOption::Setext
+            | HeadingStyleOption::SetextWithAtx
+            | HeadingStyleOption::SetextWithAtxClosed => HeadingStyle::Setext,
+        };
+        Some(style)
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let headings: Vec<_> = headings::source_headings(content)
+            .into_iter()
+            .filter(|heading| heading.top_level)
+            .collect();
+        let Some(first) = headings.first().map(|heading| heading.style) else {
+            return Vec::new();
+        };
+
+        let mut problems = Vec::new();
+        for heading in &headings {
+            let text = match heading.style {
+                HeadingStyle::Setext => lines[heading.first_line..heading.last_line]
+                    .iter()
+                    .map(|line| line.trim())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => AtxLine::parse(lines[heading.first_line])
+                    .map_or("", |atx| atx.text)
+                    .to_string(),
+            };
+            let Some(expected) = self
+                .expected(heading, first, text.is_empty())
+                .filter(|&expected| expected != heading.style)
+            else {
+                continue;
+            };
+            problems.push(SourceProblem {
+                line: heading.first_line,
+                column: 1,
+                message: format!(
+                    "Heading style should be {}, found {}",
+                    expected.name(),
+                    heading.style.name()
+                ),
+                lines: heading.first_line..heading.last_line + 1,
+                replacement: render(&text, heading.level, expected),
+            });
+        }
+        problems
+    }
+}
+
+/// Writes a heading with `text` in `style`. Setext cannot express a heading
+/// without text, which stays ATX.
+fn render(text: &str, level: usize, style: HeadingStyle) -> Vec<String> {
+    let hashes = "#".repeat(level);
+    match style {
+        HeadingStyle::Atx | HeadingStyle::Setext if text.is_empty() => vec![hashes],
+        HeadingStyle::Atx => vec![format!("{} {}", hashes, text)],
+        HeadingStyle::AtxClosed if text.is_empty() => vec![format!("{} {}", hashes, hashes)],
+        HeadingStyle::AtxClosed => vec![format!("{} {} {}", hashes, text, hashes)],
+        HeadingStyle::Setext => {
+            let underline = if level == 1 { "=" } else { "-" };
+            vec![text.to_string(), underline.repeat(text.width().max(3))]
+        }
+    }
+}
+
+impl Rule for HeadingStyleRule {
+    fn id(&self) -> &'static str {
+        "MD003"
+    }
+
+    fn name(&self) -> &'static str {
+        "Heading Style"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures headings are written in one style: ATX, closed ATX or setext."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["heading-style"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings written in a different style than the others: ATX
+(`# Heading`), closed ATX (`# Heading #`) or setext (a line underlined with
+`=` or `-`). With the default `style = "consistent"` every heading must be
+written like the first one; `"atx"`, `"atx_closed"`, `"setext"`,
+`"setext_with_atx"` and `"setext_with_atx_closed"` require a style. Setext can
+only express levels 1 and 2, so with `"setext"` deeper headings may use either
+ATX style, while `"setext_with_atx"` expects ATX and `"setext_with_atx_closed"`
+closed ATX. The style is set under `[rules.md003]`.
+
+Headings in lists and block quotes are not checked. `md-check fix` rewrites
+headings in the expected style.
+
+Bad:
+
+```markdown
+# Title
+
+Usage
+-----
+```
+
+Good:
+
+```markdown
+# Title
+
+## Usage
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+    use crate::linter::fix_with_config;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_consistent_style() {
+        let content = "Title\n=====\n\n## Usage ##\n\n### Details\n";
+
+        assert_eq!(
+            problems(content, "MD003"),
+            vec![(
+                4,
+                Some(1),
+                "Heading style should be setext, found atx_closed".to_string()
+            )]
+        );
+        assert_eq!(
+            HeadingStyleRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "Title\n=====\n\nUsage\n-----\n\n### Details\n"
+        );
+    }
+
+    #[test]
+    fn test_required_style() {
+        let rule = HeadingStyleRule::with_config(HeadingStyleConfig {
+            style: HeadingStyleOption::AtxClosed,
+        });
+        let content = "Title\n=====\n\n## Usage\n\n> ## Quoted\n";
+
+        assert_eq!(
+            rule.fix(content, &Suppressions::default()).unwrap(),
+            "# Title #\n\n## Usage ##\n\n> ## Quoted\n"
+        );
+    }
+
+    #[test]
+    fn test_setext_styles_for_deeper_headings() {
+        let content = "# Title\n\n### Open\n\n### Closed ###\n";
+        let fix = |style| {
+            HeadingStyleRule::with_config(HeadingStyleConfig { style })
+                .fix(content, &Suppressions::default())
+                .unwrap()
+        };
+
+        assert_eq!(
+            fix(HeadingStyleOption::Setext),
+            "Title\n=====\n\n### Open\n\n### Closed ###\n"
+        );
+        assert_eq!(
+            fix(HeadingStyleOption::SetextWithAtx),
+            "Title\n=====\n\n### Open\n\n### Closed\n"
+        );
+        assert_eq!(
+            fix(HeadingStyleOption::SetextWithAtxClosed),
+            "Title\n=====\n\n### Open ###\n\n### Closed ###\n"
+        );
+    }
+
+    #[test]
+    fn test_headings_without_text() {
+        let content = "# Title\n\n# #\n\n### ###\n";
+        let fixed = |style| {
+            let config = RulesConfig {
+                md003: HeadingStyleConfig { style },
+                ..RulesConfig::default()
+            };
+            let once = fix_with_config(content, &config);
+            assert_eq!(fix_with_config(&once, &config), once);
+            once
+        };
+
+        assert_eq!(
+            fixed(HeadingStyleOption::Consistent),
+            "# Title\n\n#\n\n###\n"
+        );
+        assert_eq!(fixed(HeadingStyleOption::Atx), "# Title\n\n#\n\n###\n");
+        assert_eq!(
+            fixed(HeadingStyleOption::AtxClosed),
+            "# Title #\n\n# #\n\n### ###\n"
+        );
+        assert_eq!(
+            fixed(HeadingStyleOption::Setext),
+            "Title\n=====\n\n# #\n\n### ###\n"
+        );
+        assert_eq!(
+            fixed(HeadingStyleOption::SetextWithAtx),
+            "Title\n=====\n\n#\n\n###\n"
+        );
+        assert!(problems("# #\n\n### ###\n", "MD003").is_empty());
+    }
+}
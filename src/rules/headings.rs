@@ -0,0 +1,257 @@
+//! State shared by the heading rules, which need more than one event to
+//! make a decision, and the source-level view of headings used by the rules
+//! that check how headings are written.
+
+use super::common::PARSER_OPTIONS;
+use crate::text::LineIndex;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::cell::{Cell, RefCell};
+use std::sync::LazyLock;
+
+/// Matches the `title` key of YAML or TOML front matter, as markdownlint does.
+static TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*"?title"?\s*[:=]\s*(.*?)\s*$"#).unwrap());
+
+/// Extracts the `title` value from front matter, without quotes.
+pub fn front_matter_title(metadata: &str) -> Option<String> {
+    let value = TITLE.captures(metadata)?.get(1)?.as_str();
+    let unquoted = ['"', '\'']
+        .iter()
+        .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value);
+    Some(unquoted.to_string())
+}
+
+/// Follows the front matter block at the start of a document.
+#[derive(Default)]
+pub struct FrontMatter {
+    inside: Cell<bool>,
+    title: RefCell<Option<String>>,
+}
+
+impl FrontMatter {
+    /// Records `event` if it belongs to the front matter, and returns
+    /// whether it did.
+    pub fn observe(&self, event: &Event<'_>) -> bool {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => self.inside.set(true),
+            Event::End(TagEnd::MetadataBlock(_)) => self.inside.set(false),
+            Event::Text(text) if self.inside.get() => {
+                let mut title = self.title.borrow_mut();
+                if title.is_none() {
+                    *title = front_matter_title(text);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn title(&self) -> Option<String> {
+        self.title.borrow().clone()
+    }
+}
+
+/// Collects the plain text of a heading between its start and end events.
+#[derive(Default)]
+pub struct HeadingText {
+    text: RefCell<Option<String>>,
+}
+
+impl HeadingText {
+    pub fn start(&self) {
+        *self.text.borrow_mut() = Some(String::new());
+    }
+
+    /// Appends the text of `event` if a heading is being collected.
+    pub fn push(&self, event: &Event<'_>) {
+        if let Some(text) = self.text.borrow_mut().as_mut()
+            && let Event::Text(part) | Event::Code(part) = event
+        {
+            text.push_str(part);
+        }
+    }
+
+    /// The collected text, once the heading has ended.
+    pub fn finish(&self) -> Option<String> {
+        self.text.borrow_mut().take()
+    }
+}
+
+/// How a heading is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// `## Heading`
+    Atx,
+    /// `## Heading ##`
+    AtxClosed,
+    /// `Heading` underlined with `=` or `-`.
+    Setext,
+}
+
+impl HeadingStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            HeadingStyle::Atx => "atx",
+            HeadingStyle::AtxClosed => "atx_closed",
+            HeadingStyle::Setext => "setext",
+        }
+    }
+}
+
+/// A heading as it appears in the source, by 0-based line index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceHeading {
+    pub level: usize,
+    pub style: HeadingStyle,
+    pub first_line: usize,
+    /// Last line of the heading, the underline of a setext heading.
+    pub last_line: usize,
+    /// Whether the heading is outside list items and block quotes, so its
+    /// lines hold nothing but the heading.
+    pub top_level: bool,
+}
+
+/// Finds the headings of `content` in document order.
+pub fn source_headings(content: &str) -> Vec<SourceHeading> {
+    let line_index = LineIndex::new(content);
+    let mut depth = 0usize;
+    let mut headings = Vec::new();
+    for (event, range) in Parser::new_ext(content, PARSER_OPTIONS).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Item | Tag::BlockQuote(_)) => depth += 1,
+            Event::End(TagEnd::Item | TagEnd::BlockQuote(_)) => depth -= 1,
+            Event::Start(Tag::Heading { level, .. }) => {
+                let source = &content[range.clone()];
+                let style = match source.lines().next().and_then(AtxLine::parse) {
+                    Some(atx) if atx.is_closed() => HeadingStyle::AtxClosed,
+                    Some(_) => HeadingStyle::Atx,
+                    None => HeadingStyle::Setext,
+                };
+                let end = range.end.saturating_sub(1).max(range.start);
+                headings.push(SourceHeading {
+                    level: level as usize,
+                    style,
+                    first_line: line_index.line(range.start) - 1,
+                    last_line: line_index.line(end) - 1,
+                    top_level: depth == 0,
+                });
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+/// The parts of a line that starts like an ATX heading, `## Text ##`,
+/// whether or not it is one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtxLine<'a> {
+    pub indent: &'a str,
+    pub open: &'a str,
+    /// Whitespace between the opening hashes and the text.
+    pub before: &'a str,
+    pub text: &'a str,
+    /// Whitespace between the text and the closing hashes.
+    pub after: &'a str,
+    /// Closing hashes; empty if there are none.
+    pub close: &'a str,
+}
+
+impl<'a> AtxLine<'a> {
+    /// Splits `line`, or returns `None` if it does not start with one to six
+    /// hashes indented by at most three spaces.
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let indent_len = line.len() - line.trim_start_matches(' ').len();
+        if indent_len > 3 {
+            return None;
+        }
+        let (indent, rest) = line.split_at(indent_len);
+        let open_len = rest.len() - rest.trim_start_matches('#').len();
+        if !(1..=6).contains(&open_len) {
+            return None;
+        }
+        let (open, rest) = rest.split_at(open_len);
+        let body = rest.trim_start_matches([' ', '\t']);
+        let before = &rest[..rest.len() - body.len()];
+        let body = body.trim_end_matches([' ', '\t']);
+        let inner = body.trim_end_matches('#');
+        // An escaped hash is text; a heading of hashes only, `### ###`, has
+        // no text and closes with all of them
+        let (inner, close) = if inner.ends_with('\\') {
+            (body, "")
+        } else if inner.is_empty() {
+            ("", body)
+        } else {
+            (inner, &body[inner.len()..])
+        };
+        let text = inner.trim_end_matches([' ', '\t']);
+        Some(Self {
+            indent,
+            open,
+            before,
+            text,
+            after: &inner[text.len()..],
+            close,
+        })
+    }
+
+    /// Whether the line is a closed ATX heading, with whitespace before the
+    /// closing hashes. Without text, that whitespace is `before`.
+    pub fn is_closed(&self) -> bool {
+        let space = if self.text.is_empty() {
+            self.before
+        } else {
+            self.after
+        };
+        !self.close.is_empty() && !space.is_empty()
+    }
+
+    /// Byte offset of the text within the line.
+    pub fn text_offset(&self) -> usize {
+        self.indent.len() + self.open.len() + self.before.len()
+    }
+
+    /// The line with the given whitespace around the text.
+    pub fn rebuild(&self, before: &str, after: &str) -> String {
+        let after = if self.close.is_empty() { "" } else { after };
+        format!(
+            "{}{}{}{}{}{}",
+            self.indent, self.open, before, self.text, after, self.close
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_closed_heading() {
+        let atx = AtxLine::parse("### ###").unwrap();
+        assert_eq!((atx.text, atx.close), ("", "###"));
+        assert!(atx.is_closed());
+
+        let atx = AtxLine::parse("# #").unwrap();
+        assert_eq!((atx.text, atx.close), ("", "#"));
+        assert!(atx.is_closed());
+
+        let atx = AtxLine::parse("# \\#").unwrap();
+        assert_eq!((atx.text, atx.close), ("\\#", ""));
+        assert!(!atx.is_closed());
+    }
+
+    #[test]
+    fn test_front_matter_title() {
+        assert_eq!(
+            front_matter_title("layout: post\ntitle: \"Getting started\"\n"),
+            Some("Getting started".to_string())
+        );
+        assert_eq!(
+            front_matter_title("title = 'TOML'\n"),
+            Some("TOML".to_string())
+        );
+        assert_eq!(front_matter_title("subtitle: x\n"), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! A lenient scanner for the raw HTML that Markdown passes through, just
+//! enough to find tag names, attributes and comments.
+
+/// Where a scan stopped, so that the next line of an HTML block, which
+/// pulldown-cmark reports as a separate event, can continue from there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HtmlState {
+    #[default]
+    Text,
+    Comment,
+    /// Inside a tag whose attributes continue on the next line.
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlTag {
+    /// Lowercase element name; empty for the rest of a tag started on an
+    /// earlier line.
+    pub name: String,
+    pub closing: bool,
+    /// Lowercase attribute names with their unquoted values.
+    pub attributes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub struct HtmlScan {
+    pub tags: Vec<HtmlTag>,
+    pub comments: usize,
+    pub state: HtmlState,
+}
+
+/// Scans `html`, starting in `state`.
+pub fn scan(html: &str, state: HtmlState) -> HtmlScan {
+    let mut scan = HtmlScan::default();
+    let mut rest = html;
+
+    match state {
+        HtmlState::Text => {}
+        HtmlState::Comment => rest = skip_comment(rest, &mut scan.state),
+        HtmlState::Tag => {
+            rest = scan_attributes(rest, String::new(), false, &mut scan);
+        }
+    }
+
+    while scan.state == HtmlState::Text
+        && let Some(start) = rest.find('<')
+    {
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("<!--") {
+            scan.comments += 1;
+            rest = skip_comment(after, &mut scan.state);
+            continue;
+        }
+
+        let (closing, after) = match rest[1..].strip_prefix('/') {
+            Some(after) => (true, after),
+            None => (false, &rest[1..]),
+        };
+        if !after.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // `<!DOCTYPE`, `<?xml` or a lone `<`
+            rest = &rest[1..];
+            continue;
+        }
+        let end = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+            .unwrap_or(after.len());
+        let name = after[..end].to_ascii_lowercase();
+        rest = scan_attributes(&after[end..], name, closing, &mut scan);
+    }
+    scan
+}
+
+/// Skips to the end of a comment, returning what follows it.
+fn skip_comment<'a>(html: &'a str, state: &mut HtmlState) -> &'a str {
+    match html.find("-->") {
+        Some(end) => {
+            *state = HtmlState::Text;
+            &html[end + 3..]
+        }
+        None => {
+            *state = HtmlState::Comment;
+            ""
+        }
+    }
+}
+
+/// Reads attributes up to the closing `>`, records the tag and returns
+/// what follows it.
+fn scan_attributes<'a>(
+    mut html: &'a str,
+    name: String,
+    closing: bool,
+    scan: &mut HtmlScan,
+) -> &'a str {
+    let mut attributes = Vec::new();
+    scan.state = HtmlState::Tag;
+
+    loop {
+        html = html.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        if let Some(after) = html.strip_prefix('>') {
+            html = after;
+            scan.state = HtmlState::Text;
+            break;
+        }
+        if html.is_empty() {
+            break;
+        }
+
+        let end = html
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(html.len())
+            .max(1);
+        let attribute = html[..end].to_ascii_lowercase();
+        html = html[end..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after) = html.strip_prefix('=') {
+            let after = after.trim_start();
+            let (raw, rest) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    match inner.find(quote) {
+                        Some(end) => (&inner[..end], &inner[end + 1..]),
+                        None => (inner, ""),
+                    }
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = raw.to_string();
+            html = rest;
+        }
+        attributes.push((attribute, value));
+    }
+
+    scan.tags.push(HtmlTag {
+        name,
+        closing,
+        attributes,
+    });
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_tags_and_attributes() {
+        let scan = scan(
+            r#"<DIV class="a b" hidden><!-- <b> --></div><img src=x.png alt='A'>"#,
+            HtmlState::Text,
+        );
+
+        let names: Vec<_> = scan
+            .tags
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.closing))
+            .collect();
+        assert_eq!(names, vec![("div", false), ("div", true), ("img", false)]);
+        assert_eq!(
+            scan.tags[0].attributes,
+            vec![
+                ("class".to_string(), "a b".to_string()),
+                ("hidden".to_string(), String::new())
+            ]
+        );
+        assert_eq!(
+            scan.tags[2].attributes[1],
+            ("alt".to_string(), "A".to_string())
+        );
+        assert_eq!(scan.comments, 1);
+        assert_eq!(scan.state, HtmlState::Text);
+    }
+
+    #[test]
+    fn test_scan_continues_across_lines() {
+        let first = scan("<!-- start <b>\n", HtmlState::Text);
+        assert_eq!(first.state, HtmlState::Comment);
+        assert!(first.tags.is_empty());
+
+        let second = scan("end --> <img\n", first.state);
+        assert_eq!(second.state, HtmlState::Tag);
+
+        let third = scan("  onerror=\"x()\">\n", second.state);
+        assert_eq!(third.state, HtmlState::Text);
+        assert_eq!(third.tags[0].name, "");
+        assert_eq!(third.tags[0].attributes[0].0, "onerror");
+    }
+}
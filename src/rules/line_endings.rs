@@ -0,0 +1,141 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use crate::config::LineEndingStyle;
+use crate::suppression::Suppressions;
+use crate::text::{LineEnding, lines_with_endings};
+use pulldown_cmark::Event;
+
+pub struct LineEndingsRule {
+    style: LineEndingStyle,
+}
+
+impl Default for LineEndingsRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineEndingsRule {
+    pub fn new() -> Self {
+        Self::with_style(LineEndingStyle::default())
+    }
+
+    pub fn with_style(style: LineEndingStyle) -> Self {
+        Self { style }
+    }
+
+    fn expected(&self, first_line_ending: Option<LineEnding>) -> Option<LineEnding> {
+        match self.style {
+            LineEndingStyle::Consistent => first_line_ending,
+            LineEndingStyle::Lf => Some(LineEnding::Lf),
+            LineEndingStyle::Crlf => Some(LineEnding::Crlf),
+        }
+    }
+}
+
+impl Rule for LineEndingsRule {
+    fn id(&self) -> &'static str {
+        "LINE_ENDINGS"
+    }
+
+    fn name(&self) -> &'static str {
+        "Line Endings"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures lines end with the configured terminator (LF, CRLF, or consistent within a file)"
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::WHITESPACE]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports lines whose terminator differs from the configured style. With the
+default `style = "consistent"` every line must end like the first line of
+the file; `"lf"` and `"crlf"` require that terminator everywhere. Mixed line
+endings make diffs show unchanged lines as modified.
+
+`md-check fix` converts every line ending to the expected one.
+
+Bad (`style = "lf"`):
+
+```text
+# Title\r\n
+\r\n
+Text\r\n
+```
+
+Good:
+
+```text
+# Title\n
+\n
+Text\n
+```"#
+    }
+
+    // Called once per line, like MD012
+    fn check(&self, _event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        let found = context.line_ending?;
+        let expected = self.expected(context.first_line_ending)?;
+        if found == expected {
+            return None;
+        }
+
+        Some(LintError {
+            file_path: context.file_path.clone(),
+            line: context.current_line_number,
+            column: Some(context.line_text.chars().count() + 1),
+            message: format!(
+                "Expected {} line ending, found {}",
+                expected.name(),
+                found.name()
+            ),
+            rule_id: self.id().to_string(),
+        })
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        let first_line_ending =
+            lines_with_endings(content).find_map(|(_, ending)| LineEnding::from_terminator(ending));
+        let expected = self.expected(first_line_ending)?;
+
+        let mut fixed = String::with_capacity(content.len());
+        for (idx, (line, ending)) in lines_with_endings(content).enumerate() {
+            fixed.push_str(line);
+            if ending.is_empty() || self.is_suppressed(suppressions, idx + 1) {
+                fixed.push_str(ending);
+            } else {
+                fixed.push_str(expected.as_str());
+            }
+        }
+        Some(fixed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_line_endings() {
+        let content = "# Title\r\n\nText\rMore";
+
+        assert_eq!(
+            LineEndingsRule::new()
+                .fix(content, &Suppressions::default())
+                .as_deref(),
+            Some("# Title\r\n\r\nText\r\nMore")
+        );
+        assert_eq!(
+            LineEndingsRule::with_style(LineEndingStyle::Lf)
+                .fix(content, &Suppressions::default())
+                .as_deref(),
+            Some("# Title\n\nText\nMore")
+        );
+    }
+}
@@ -1,7 +1,11 @@
-use super::common::{LintContext, LintError, Rule};
+use super::common::{LineKind, LintContext, LintError, Rule, tags};
+use crate::config::LineTooLongConfig;
 use pulldown_cmark::Event;
+use unicode_width::UnicodeWidthStr;
 
-pub struct LineTooLongRule;
+pub struct LineTooLongRule {
+    config: LineTooLongConfig,
+}
 
 impl Default for LineTooLongRule {
     fn default() -> Self {
@@ -11,7 +15,10 @@ impl Default for LineTooLongRule {
 
 impl LineTooLongRule {
     pub fn new() -> Self {
-        Self
+        Self::with_config(LineTooLongConfig::default())
+    }
+    pub fn with_config(config: LineTooLongConfig) -> Self {
+        Self { config }
     }
 }
 
@@ -23,20 +30,201 @@ impl Rule for LineTooLongRule {
         "Line Too Long"
     }
     fn description(&self) -> &'static str {
-        "Ensures lines do not exceed 100 characters."
+        "Ensures lines do not exceed the configured length (100 columns by default)."
+    }
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LINE_LENGTH]
+    }
+    fn aliases(&self) -> &'static [&'static str] {
+        &["MD013", "line-length"]
     }
+    fn explanation(&self) -> &'static str {
+        r#"Reports lines wider than `line_length` display columns (100 by default, set in
+`[rules.line_too_long]`). Width is measured in columns on screen, so CJK
+characters and emoji count twice. Long lines are hard to read in editors and
+produce noisy diffs; wrap prose onto several lines instead.
+
+Code blocks and headings can have their own limits with
+`code_block_line_length` and `heading_line_length`, or be skipped with
+`code_blocks = false` and `headings = false`; `tables = false` skips table
+rows. A line that is only too long because it ends in a link that cannot be
+wrapped, such as a long URL, is accepted unless `strict = true`.
+
+Bad:
+
+```markdown
+This sentence goes on and on, well past the point where it fits comfortably on a single line of an editor.
+```
+
+Good:
 
+```markdown
+This sentence goes on and on, well past the point where it fits
+comfortably on a single line of an editor.
+```"#
+    }
     fn check(&self, _event: &Event<'_>, context: &LintContext) -> Option<LintError> {
-        // check length of the line text in characters, not bytes
-        if context.line_text.chars().count() > 100 {
-            Some(LintError {
-                file_path: context.file_path.clone(),
-                line: context.current_line_number,
-                message: "Line exceeds 100 characters limit".to_string(),
-                rule_id: self.id().to_string(),
-            })
-        } else {
-            None
+        let config = &self.config;
+        let (limit, what) = match context.line_kind {
+            LineKind::Code if !config.code_blocks => return None,
+            LineKind::Code => (
+                config.code_block_line_length.unwrap_or(config.line_length),
+                "Code block line",
+            ),
+            LineKind::Heading if !config.headings => return None,
+            LineKind::Heading => (
+                config.heading_line_length.unwrap_or(config.line_length),
+                "Heading",
+            ),
+            LineKind::Table if !config.tables => return None,
+            LineKind::Table | LineKind::Text | LineKind::Html | LineKind::FrontMatter => {
+                (config.line_length, "Line")
+            }
+        };
+
+        let line = context.line_text.as_str();
+        if line.width() <= limit {
+            return None;
         }
+        let (overflow, column) = first_overflow(line, limit);
+        if !config.strict && ends_in_unbreakable_link(line, overflow) {
+            return None;
+        }
+        Some(LintError {
+            file_path: context.file_path.clone(),
+            line: context.current_line_number,
+            column: Some(column),
+            message: format!("{} exceeds {} column limit", what, limit),
+            rule_id: self.id().to_string(),
+        })
+    }
+}
+
+/// Byte offset and 1-based character column of the first character that
+/// reaches past `limit` display columns.
+fn first_overflow(line: &str, limit: usize) -> (usize, usize) {
+    line.char_indices()
+        .enumerate()
+        .find(|(_, (offset, c))| line[..offset + c.len_utf8()].width() > limit)
+        .map_or(
+            (line.len(), line.chars().count() + 1),
+            |(idx, (offset, _))| (offset, idx + 1),
+        )
+}
+
+/// Whether everything from `overflow` on belongs to the last word of `line`
+/// and that word is a link, which wrapping cannot shorten.
+fn ends_in_unbreakable_link(line: &str, overflow: usize) -> bool {
+    let start = line[..overflow]
+        .rfind(char::is_whitespace)
+        .map_or(0, |idx| {
+            idx + line[idx..].chars().next().map_or(1, char::len_utf8)
+        });
+    let word = &line[start..];
+    !word.contains(char::is_whitespace)
+        && (word.contains("://") || word.contains("](") || word.starts_with('<'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+    use crate::linter::lint_with_config;
+    use std::path::Path;
+
+    fn problems(content: &str, config: LineTooLongConfig) -> Vec<(usize, Option<usize>, String)> {
+        let rules = RulesConfig {
+            line_too_long: config,
+            ..RulesConfig::default()
+        };
+        lint_with_config(content, Path::new("test.md"), &rules)
+            .into_iter()
+            .filter(|e| e.rule_id == "LINE_TOO_LONG")
+            .map(|e| (e.line, e.column, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_separate_limits_per_block() {
+        let content = format!(
+            "# {heading}\n\n{prose}\n\n```\n{code}\n```\n\n| a | b |\n| - | - |\n| {prose} | x |\n",
+            heading = "h".repeat(20),
+            prose = "p".repeat(15),
+            code = "c".repeat(25),
+        );
+        let config = LineTooLongConfig {
+            line_length: 10,
+            code_block_line_length: Some(30),
+            heading_line_length: Some(15),
+            ..LineTooLongConfig::default()
+        };
+
+        assert_eq!(
+            problems(&content, config.clone()),
+            vec![
+                (1, Some(16), "Heading exceeds 15 column limit".to_string()),
+                (3, Some(11), "Line exceeds 10 column limit".to_string()),
+                (11, Some(11), "Line exceeds 10 column limit".to_string()),
+            ]
+        );
+
+        let config = LineTooLongConfig {
+            code_block_line_length: Some(20),
+            headings: false,
+            tables: false,
+            ..config
+        };
+        assert_eq!(
+            problems(&content, config),
+            vec![
+                (3, Some(11), "Line exceeds 10 column limit".to_string()),
+                (
+                    6,
+                    Some(21),
+                    "Code block line exceeds 20 column limit".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_count_twice() {
+        let config = LineTooLongConfig {
+            line_length: 10,
+            ..LineTooLongConfig::default()
+        };
+
+        assert!(problems("日本語の文\n", config.clone()).is_empty());
+        assert_eq!(
+            problems("日本語の文章\n", config.clone()),
+            vec![(1, Some(6), "Line exceeds 10 column limit".to_string())],
+            "the sixth character ends at column 12"
+        );
+        assert!(problems("🚀🚀🚀🚀🚀\n", config.clone()).is_empty());
+        assert_eq!(problems("🚀🚀🚀🚀🚀!\n", config)[0].1, Some(6));
+    }
+
+    #[test]
+    fn test_trailing_link_is_exempt_unless_strict() {
+        let content = "See [the docs](https://example.com/a/very/long/path)\n";
+        let config = LineTooLongConfig {
+            line_length: 20,
+            ..LineTooLongConfig::default()
+        };
+
+        assert!(problems(content, config.clone()).is_empty());
+        assert_eq!(
+            problems(
+                "See https://example.com/a/long/path for more\n",
+                config.clone()
+            )
+            .len(),
+            1
+        );
+        let strict = LineTooLongConfig {
+            strict: true,
+            ..config
+        };
+        assert_eq!(problems(content, strict).len(), 1);
     }
 }
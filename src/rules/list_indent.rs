@@ -0,0 +1,142 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct ListIndentRule;
+
+impl Default for ListIndentRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListIndentRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let mut problems = Vec::new();
+        for list in lists::source_lists(content) {
+            // Items that share a line with a block quote or a parent marker
+            // cannot be moved on their own
+            let mut items = list
+                .items
+                .iter()
+                .filter(|item| lines[item.line][..item.offset].trim().is_empty());
+            let Some(first) = items.next() else {
+                continue;
+            };
+            let indent = &lines[first.line][..first.offset];
+            let width = indent.chars().count();
+            for item in items {
+                let line = lines[item.line];
+                // Numbers may also be aligned on the right
+                let aligned = list.ordered && item.spaces_offset() == first.spaces_offset();
+                if line[..item.offset] == *indent || aligned {
+                    continue;
+                }
+                problems.push(SourceProblem {
+                    line: item.line,
+                    column: fixes::column_at(line, item.offset),
+                    message: format!(
+                        "Inconsistent indentation for list items at the same level: expected {}, found {}",
+                        width,
+                        line[..item.offset].chars().count()
+                    ),
+                    lines: item.line..item.line + 1,
+                    replacement: vec![format!("{}{}", indent, &line[item.offset..])],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for ListIndentRule {
+    fn id(&self) -> &'static str {
+        "MD005"
+    }
+
+    fn name(&self) -> &'static str {
+        "List Indent"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures items of the same list are indented alike."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["list-indent"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports list items indented differently from the first item of their list.
+Parsers still put them in the same list, but the source looks nested when it
+is not. Ordered lists may instead align their numbers on the right, so that
+`9.` and `10.` end in the same column.
+
+`md-check fix` indents the items like the first one.
+
+Bad:
+
+```markdown
+- Install
+ - Configure
+```
+
+Good:
+
+```markdown
+- Install
+- Configure
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_inconsistent_indentation() {
+        let content = "- a\n - b\n\n 9. c\n10. d\n\nText\n\n1. e\n 2. f\n";
+
+        assert_eq!(
+            positions(content, "MD005"),
+            vec![(2, Some(2)), (10, Some(2))]
+        );
+        assert_eq!(
+            ListIndentRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "- a\n- b\n\n 9. c\n10. d\n\nText\n\n1. e\n2. f\n"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists;
+use crate::config::ListMarkerSpaceConfig;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct ListMarkerSpaceRule {
+    config: ListMarkerSpaceConfig,
+}
+
+impl Default for ListMarkerSpaceRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListMarkerSpaceRule {
+    pub fn new() -> Self {
+        Self::with_config(ListMarkerSpaceConfig::default())
+    }
+
+    pub fn with_config(config: ListMarkerSpaceConfig) -> Self {
+        Self { config }
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let mut problems = Vec::new();
+        for list in lists::source_lists(content) {
+            let expected = match (list.ordered, list.loose) {
+                (false, false) => self.config.ul_single,
+                (true, false) => self.config.ol_single,
+                (false, true) => self.config.ul_multi,
+                (true, true) => self.config.ol_multi,
+            };
+            for item in &list.items {
+                let found = item.spaces.chars().count();
+                // Five or more spaces start an indented code block, not text
+                if found == 0 || found == expected || found > 4 {
+                    continue;
+                }
+                let line = lines[item.line];
+                let start = item.spaces_offset();
+                problems.push(SourceProblem {
+                    line: item.line,
+                    column: fixes::column_at(line, start),
+                    message: format!(
+                        "Spaces after list marker: expected {}, found {}",
+                        expected, found
+                    ),
+                    lines: item.line..item.line + 1,
+                    replacement: vec![format!(
+                        "{}{}{}",
+                        &line[..start],
+                        " ".repeat(expected),
+                        &line[start + item.spaces.len()..]
+                    )],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for ListMarkerSpaceRule {
+    fn id(&self) -> &'static str {
+        "MD030"
+    }
+
+    fn name(&self) -> &'static str {
+        "List Marker Space"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures a set number of spaces follows list markers."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["list-marker-space"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports list items with a different number of spaces after the marker than
+configured under `[rules.md030]`: `ul_single` and `ol_single` for unordered
+and ordered lists whose items are single paragraphs, `ul_multi` and
+`ol_multi` for loose lists, whose items are separated by blank lines or hold
+several paragraphs. All four default to 1. The column points at the spaces.
+
+`md-check fix` sets the number of spaces.
+
+Bad:
+
+```markdown
+-   Install
+1.  Configure
+```
+
+Good:
+
+```markdown
+- Install
+1. Configure
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_spaces_after_markers() {
+        let content = "-  a\n- b\n\n1.\tc\n\n-\n";
+
+        assert_eq!(
+            problems(content, "MD030"),
+            vec![(
+                1,
+                Some(2),
+                "Spaces after list marker: expected 1, found 2".to_string()
+            )]
+        );
+        assert_eq!(
+            ListMarkerSpaceRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "- a\n- b\n\n1.\tc\n\n-\n"
+        );
+    }
+
+    #[test]
+    fn test_loose_lists() {
+        let rule = ListMarkerSpaceRule::with_config(ListMarkerSpaceConfig {
+            ol_multi: 2,
+            ..ListMarkerSpaceConfig::default()
+        });
+
+        assert_eq!(
+            rule.fix(
+                "1. a\n\n1. b\n\nText\n\n1. c\n1. d\n",
+                &Suppressions::default()
+            )
+            .unwrap(),
+            "1.  a\n\n1.  b\n\nText\n\n1. c\n1. d\n"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! The source-level view of lists used by the rules that check how lists
+//! are written.
+
+use super::common::PARSER_OPTIONS;
+use crate::text::{LineIndex, lines_with_endings};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+/// A list item as it appears in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    /// 0-based index of the line with the marker.
+    pub line: usize,
+    /// Byte offset of the marker in its line.
+    pub offset: usize,
+    /// `-`, `*` or `+`, or the number and delimiter, such as `3.`.
+    pub marker: String,
+    /// Whitespace between the marker and the content; empty if the item
+    /// is empty on the marker line.
+    pub spaces: String,
+}
+
+impl ListItem {
+    /// Byte offset of the whitespace after the marker.
+    pub fn spaces_offset(&self) -> usize {
+        self.offset + self.marker.len()
+    }
+
+    /// The number of an ordered list item.
+    pub fn number(&self) -> Option<u64> {
+        self.marker[..self.marker.len() - 1].parse().ok()
+    }
+}
+
+/// A list as it appears in the source, by 0-based line index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceList {
+    pub ordered: bool,
+    /// Whether blank lines separate the items or their paragraphs.
+    pub loose: bool,
+    /// Indices of the lists this one is nested in, outermost first.
+    pub parents: Vec<usize>,
+    /// Whether the list is inside a block quote, so its lines start with `>`.
+    pub quoted: bool,
+    pub first_line: usize,
+    /// Last line that is not blank.
+    pub last_line: usize,
+    pub items: Vec<ListItem>,
+}
+
+/// Finds the lists of `content`, in the order they start.
+pub fn source_lists(content: &str) -> Vec<SourceList> {
+    let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+    let line_index = LineIndex::new(content);
+    let mut lists: Vec<SourceList> = Vec::new();
+    // Open lists, and whether the innermost open container is an item
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_item: Vec<bool> = Vec::new();
+    let mut quotes = 0usize;
+    for (event, range) in Parser::new_ext(content, PARSER_OPTIONS).into_offset_iter() {
+        match event {
+            Event::Start(Tag::List(first_number)) => {
+                let first_line = line_index.line(range.start) - 1;
+                let mut last_line =
+                    line_index.line(range.end.saturating_sub(1).max(range.start)) - 1;
+                while last_line > first_line && lines[last_line].trim().is_empty() {
+                    last_line -= 1;
+                }
+                lists.push(SourceList {
+                    ordered: first_number.is_some(),
+                    loose: false,
+                    parents: open.clone(),
+                    quoted: quotes > 0,
+                    first_line,
+                    last_line,
+                    items: Vec::new(),
+                });
+                open.push(lists.len() - 1);
+                in_item.push(false);
+            }
+            Event::End(TagEnd::List(_)) => {
+                open.pop();
+                in_item.pop();
+            }
+            Event::Start(Tag::Item) => {
+                let (line, column) = line_index.line_col(content, range.start);
+                let text = lines[line - 1];
+                let offset = text
+                    .char_indices()
+                    .nth(column - 1)
+                    .map_or(text.len(), |(offset, _)| offset);
+                if let (Some(&list), Some(item)) = (open.last(), parse_item(text, offset, line - 1))
+                {
+                    lists[list].items.push(item);
+                }
+                in_item.push(true);
+            }
+            Event::End(TagEnd::Item) => {
+                in_item.pop();
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                quotes += 1;
+                in_item.push(false);
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                quotes -= 1;
+                in_item.pop();
+            }
+            // Only loose lists wrap item text in paragraphs
+            Event::Start(Tag::Paragraph) if in_item.last() == Some(&true) => {
+                if let Some(&list) = open.last() {
+                    lists[list].loose = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    lists
+}
+
+/// Reads the item that starts at byte `offset` of `text`. The item may
+/// start with the indentation before its marker.
+fn parse_item(text: &str, offset: usize, line: usize) -> Option<ListItem> {
+    let rest = text[offset..].trim_start_matches([' ', '\t']);
+    let offset = text.len() - rest.len();
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker_len = match rest[digits..].chars().next()? {
+        '.' | ')' if digits > 0 => digits + 1,
+        '-' | '*' | '+' if digits == 0 => 1,
+        _ => return None,
+    };
+    let after = &rest[marker_len..];
+    let content = after.trim_start_matches([' ', '\t']);
+    let spaces = if content.is_empty() {
+        ""
+    } else {
+        &after[..after.len() - content.len()]
+    };
+    Some(ListItem {
+        line,
+        offset,
+        marker: rest[..marker_len].to_string(),
+        spaces: spaces.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_lists() {
+        let content = "Text\n\n - a\n   * b\n\n     c\n - d\n\n> 1) e\n> 2)\n";
+        let lists = source_lists(content);
+        let summary: Vec<_> = lists
+            .iter()
+            .map(|list| {
+                let items: Vec<_> = list
+                    .items
+                    .iter()
+                    .map(|item| {
+                        (
+                            item.line,
+                            item.offset,
+                            item.marker.as_str(),
+                            item.spaces.len(),
+                        )
+                    })
+                    .collect();
+                (
+                    list.parents.clone(),
+                    list.loose,
+                    list.quoted,
+                    list.first_line,
+                    list.last_line,
+                    items,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    vec![],
+                    false,
+                    false,
+                    2,
+                    6,
+                    vec![(2, 1, "-", 1), (6, 1, "-", 1)]
+                ),
+                (vec![0], true, false, 3, 5, vec![(3, 3, "*", 1)]),
+                (
+                    vec![],
+                    false,
+                    true,
+                    8,
+                    9,
+                    vec![(8, 2, "1)", 1), (9, 2, "2)", 0)]
+                ),
+            ]
+        );
+        assert_eq!(lists[2].items[1].number(), Some(2));
+    }
+}
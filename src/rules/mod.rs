@@ -1,20 +1,260 @@
+mod blanks_around_headings;
+mod blanks_around_lists;
 pub mod common;
+mod first_line_heading;
+mod fixes;
+mod heading_increment;
+mod heading_start_left;
+mod heading_style;
+mod headings;
+mod html;
+mod line_endings;
 mod line_too_long;
+mod list_indent;
+mod list_marker_space;
+mod lists;
 mod no_consecutive_empty_lines;
+mod no_duplicate_heading;
 mod no_empty_links;
 mod no_html;
+mod no_missing_space_atx;
+mod no_missing_space_closed_atx;
+mod no_multiple_space_atx;
+mod no_multiple_space_closed_atx;
+mod no_trailing_punctuation;
+mod ol_prefix;
+mod single_title;
+mod ul_indent;
+mod ul_style;
+mod unsafe_html;
+mod url_hygiene;
 
+pub use blanks_around_headings::BlanksAroundHeadingsRule;
+pub use blanks_around_lists::BlanksAroundListsRule;
+pub use first_line_heading::FirstLineHeadingRule;
+pub use heading_increment::HeadingIncrementRule;
+pub use heading_start_left::HeadingStartLeftRule;
+pub use heading_style::HeadingStyleRule;
+pub use line_endings::LineEndingsRule;
 pub use line_too_long::LineTooLongRule;
+pub use list_indent::ListIndentRule;
+pub use list_marker_space::ListMarkerSpaceRule;
 pub use no_consecutive_empty_lines::NoConsecutiveEmptyLinesRule;
+pub use no_duplicate_heading::NoDuplicateHeadingRule;
 pub use no_empty_links::NoEmptyLinksRule;
 pub use no_html::NoHtmlRule;
+pub use no_missing_space_atx::NoMissingSpaceAtxRule;
+pub use no_missing_space_closed_atx::NoMissingSpaceClosedAtxRule;
+pub use no_multiple_space_atx::NoMultipleSpaceAtxRule;
+pub use no_multiple_space_closed_atx::NoMultipleSpaceClosedAtxRule;
+pub use no_trailing_punctuation::NoTrailingPunctuationRule;
+pub use ol_prefix::OrderedListPrefixRule;
+pub use single_title::SingleTitleRule;
+pub use ul_indent::UnorderedListIndentRule;
+pub use ul_style::UnorderedListStyleRule;
+pub use unsafe_html::UnsafeHtmlRule;
+pub use url_hygiene::UrlHygieneRule;
+
+use crate::config::RulesConfig;
+use common::Rule;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("unknown rule or tag '{0}'; run `md-check rules` to list them")]
+pub struct UnknownRuleError(pub String);
 
 // Function to get all available rules
 pub fn get_rules() -> Vec<Box<dyn common::Rule>> {
+    get_rules_with_config(&RulesConfig::default())
+}
+
+// Same as get_rules, with rule settings taken from the config file
+pub fn get_rules_with_config(config: &RulesConfig) -> Vec<Box<dyn common::Rule>> {
+    all_rules(config)
+        .into_iter()
+        .filter(|rule| is_enabled(rule.as_ref(), config))
+        .collect()
+}
+
+/// Every rule, whether enabled or not, configured from `config`.
+pub fn all_rules(config: &RulesConfig) -> Vec<Box<dyn common::Rule>> {
     vec![
         Box::new(NoEmptyLinksRule::new()),
-        Box::new(NoHtmlRule::new()),
+        Box::new(NoHtmlRule::with_config(config.no_html.clone())),
+        Box::new(UnsafeHtmlRule::new()),
         Box::new(NoConsecutiveEmptyLinesRule::new()),
-        Box::new(LineTooLongRule::new()),
+        Box::new(LineTooLongRule::with_config(config.line_too_long.clone())),
+        Box::new(UrlHygieneRule::with_config(config.url_hygiene.clone())),
+        Box::new(LineEndingsRule::with_style(config.line_endings.style)),
+        Box::new(HeadingIncrementRule::new()),
+        Box::new(HeadingStyleRule::with_config(config.md003.clone())),
+        Box::new(UnorderedListStyleRule::with_config(config.md004.clone())),
+        Box::new(ListIndentRule::new()),
+        Box::new(UnorderedListIndentRule::with_config(config.md007.clone())),
+        Box::new(NoMissingSpaceAtxRule::new()),
+        Box::new(NoMultipleSpaceAtxRule::new()),
+        Box::new(NoMissingSpaceClosedAtxRule::new()),
+        Box::new(NoMultipleSpaceClosedAtxRule::new()),
+        Box::new(BlanksAroundHeadingsRule::new()),
+        Box::new(HeadingStartLeftRule::new()),
+        Box::new(NoDuplicateHeadingRule::with_config(config.md024.clone())),
+        Box::new(SingleTitleRule::with_config(config.md025.clone())),
+        Box::new(NoTrailingPunctuationRule::with_config(config.md026.clone())),
+        Box::new(OrderedListPrefixRule::with_config(config.md029.clone())),
+        Box::new(ListMarkerSpaceRule::with_config(config.md030.clone())),
+        Box::new(BlanksAroundListsRule::new()),
+        Box::new(FirstLineHeadingRule::with_config(config.md041.clone())),
     ]
 }
+
+/// Finds a rule by ID or alias, ignoring case.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::rules::find_rule;
+///
+/// assert_eq!(find_rule("MD013").unwrap().id(), "LINE_TOO_LONG");
+/// assert_eq!(find_rule("no-inline-html").unwrap().id(), "NO_HTML");
+/// assert!(find_rule("MD999").is_none());
+/// ```
+pub fn find_rule(name: &str) -> Option<Box<dyn common::Rule>> {
+    all_rules(&RulesConfig::default())
+        .into_iter()
+        .find(|rule| rule.is_named(name))
+}
+
+/// Whether `rule` runs under `config`. Naming the rule by ID or alias in
+/// `enable` or `disable` takes precedence over naming one of its tags, and
+/// `disable` wins over `enable` at the same level.
+pub fn is_enabled(rule: &dyn Rule, config: &RulesConfig) -> bool {
+    let names_rule = |list: &[String]| list.iter().any(|name| rule.is_named(name));
+    let names_tag = |list: &[String]| {
+        list.iter()
+            .any(|name| rule.tags().iter().any(|tag| tag.eq_ignore_ascii_case(name)))
+    };
+
+    if names_rule(&config.disable) {
+        false
+    } else if names_rule(&config.enable) {
+        true
+    } else if names_tag(&config.disable) {
+        false
+    } else if names_tag(&config.enable) {
+        true
+    } else {
+        rule.enabled_by_default()
+    }
+}
+
+/// Checks that every entry of `enable` and `disable` names a rule or tag.
+pub fn validate_selection(config: &RulesConfig) -> Result<(), UnknownRuleError> {
+    let rules = all_rules(config);
+    let known = |name: &String| {
+        rules.iter().any(|rule| {
+            rule.is_named(name) || rule.tags().iter().any(|tag| tag.eq_ignore_ascii_case(name))
+        })
+    };
+    match config
+        .enable
+        .iter()
+        .chain(&config.disable)
+        .find(|name| !known(name))
+    {
+        Some(name) => Err(UnknownRuleError(name.clone())),
+        None => Ok(()),
+    }
+}
+
+/// The problems `rule_id` reports in `content` under the default
+/// configuration, as (line, column, message). Shared by the rule tests.
+#[cfg(test)]
+fn problems(content: &str, rule_id: &str) -> Vec<(usize, Option<usize>, String)> {
+    crate::linter::lint(content, std::path::Path::new("test.md"))
+        .into_iter()
+        .filter(|e| e.rule_id == rule_id)
+        .map(|e| (e.line, e.column, e.message))
+        .collect()
+}
+
+/// Like [`problems`], without the messages.
+#[cfg(test)]
+fn positions(content: &str, rule_id: &str) -> Vec<(usize, Option<usize>)> {
+    problems(content, rule_id)
+        .into_iter()
+        .map(|(line, column, _)| (line, column))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled_ids(config: &RulesConfig) -> Vec<&'static str> {
+        get_rules_with_config(config)
+            .iter()
+            .map(|rule| rule.id())
+            .collect()
+    }
+
+    #[test]
+    fn test_tags_and_rules_can_be_disabled_and_enabled() {
+        let config = RulesConfig {
+            disable: vec!["links".to_string(), "md013".to_string()],
+            enable: vec!["NO_EMPTY_LINKS".to_string()],
+            ..RulesConfig::default()
+        };
+
+        assert_eq!(
+            enabled_ids(&config),
+            vec![
+                "NO_EMPTY_LINKS",
+                "NO_HTML",
+                "UNSAFE_HTML",
+                "MD012",
+                "LINE_ENDINGS",
+                "MD001",
+                "MD003",
+                "MD004",
+                "MD005",
+                "MD007",
+                "MD018",
+                "MD019",
+                "MD020",
+                "MD021",
+                "MD022",
+                "MD023",
+                "MD024",
+                "MD025",
+                "MD026",
+                "MD029",
+                "MD030",
+                "MD032"
+            ]
+        );
+        assert_eq!(validate_selection(&config), Ok(()));
+
+        let config = RulesConfig {
+            disable: vec!["typography".to_string()],
+            ..RulesConfig::default()
+        };
+        assert_eq!(
+            validate_selection(&config),
+            Err(UnknownRuleError("typography".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_every_rule_is_documented() {
+        let docs = include_str!("../../docs/rules.md");
+        for rule in all_rules(&RulesConfig::default()) {
+            let heading = format!("\n## {}\n", rule.id());
+            assert!(docs.contains(&heading), "docs/rules.md lacks {}", heading);
+            assert!(
+                rule.tags()
+                    .iter()
+                    .all(|tag| !tag.is_empty() && tag.to_lowercase() == *tag)
+            );
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use super::common::{LintContext, LintError, Rule};
+use super::common::{LintContext, LintError, Rule, tags};
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
 use pulldown_cmark::Event;
 
 pub struct NoConsecutiveEmptyLinesRule {}
@@ -26,6 +28,42 @@ impl Rule for NoConsecutiveEmptyLinesRule {
         "Ensures no more than one consecutive empty line."
     }
 
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-multiple-blanks"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports blank lines that follow another blank line. One blank line is
+enough to separate blocks; more only add noise to diffs.
+
+`md-check fix` collapses each run of blank lines into one.
+
+Bad:
+
+```markdown
+# Title
+
+
+Text
+```
+
+Good:
+
+```markdown
+# Title
+
+Text
+```"#
+    }
+
     // NOTE: This check function assumes the linter calls it once per line,
     // providing line-specific info in the LintContext.
     // It ignores the `event` parameter in this specific rule.
@@ -36,6 +74,7 @@ impl Rule for NoConsecutiveEmptyLinesRule {
             Some(LintError {
                 file_path: context.file_path.clone(), // add file_path
                 line: context.current_line_number,
+                column: None,
                 message: "Multiple consecutive blank lines found (MD012)".to_string(),
                 rule_id: self.id().to_string(),
             })
@@ -43,4 +82,46 @@ impl Rule for NoConsecutiveEmptyLinesRule {
             None
         }
     }
+
+    // Drops every blank line that follows another blank line
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        let mut fixed = String::with_capacity(content.len());
+        let mut previous_line_was_blank = false;
+
+        for (idx, (line, ending)) in lines_with_endings(content).enumerate() {
+            let line_is_blank = line.trim().is_empty();
+            if !(line_is_blank && previous_line_was_blank)
+                || self.is_suppressed(suppressions, idx + 1)
+            {
+                fixed.push_str(line);
+                fixed.push_str(ending);
+            }
+            previous_line_was_blank = line_is_blank;
+        }
+        Some(fixed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fix_collapses_blank_lines() {
+        let rule = NoConsecutiveEmptyLinesRule::new();
+
+        assert_eq!(
+            rule.fix(
+                "# Title\r\n\r\n\r\n  \nText\r\r\r",
+                &Suppressions::default()
+            )
+            .as_deref(),
+            Some("# Title\r\n\r\nText\r\r")
+        );
+        assert_eq!(
+            rule.fix("Text\n\nMore", &Suppressions::default())
+                .as_deref(),
+            Some("Text\n\nMore")
+        );
+    }
 }
@@ -0,0 +1,250 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use super::headings::HeadingText;
+use crate::config::DuplicateHeadingConfig;
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+pub struct NoDuplicateHeadingRule {
+    config: DuplicateHeadingConfig,
+    heading: HeadingText,
+    /// Level and line of the heading being collected.
+    current: Cell<(usize, usize)>,
+    /// Line of the first heading with each text, per level. Only the first
+    /// map is used unless `siblings_only` is set.
+    seen: RefCell<Vec<HashMap<String, usize>>>,
+}
+
+impl Default for NoDuplicateHeadingRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoDuplicateHeadingRule {
+    pub fn new() -> Self {
+        Self::with_config(DuplicateHeadingConfig::default())
+    }
+
+    pub fn with_config(config: DuplicateHeadingConfig) -> Self {
+        Self {
+            config,
+            heading: HeadingText::default(),
+            current: Cell::new((0, 0)),
+            seen: RefCell::new(vec![HashMap::new(); 7]),
+        }
+    }
+
+    /// The text headings are compared by.
+    fn key(&self, text: &str) -> String {
+        let mut key = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if self.config.ignore_case {
+            key = key.to_lowercase();
+        }
+        if self.config.ignore_punctuation {
+            key.retain(|c| !c.is_ascii_punctuation());
+        }
+        key
+    }
+
+    /// Line of the earlier heading that the heading ending now duplicates.
+    fn duplicate(&self, text: &str) -> Option<usize> {
+        let key = self.key(text);
+        if key.trim().is_empty() {
+            return None;
+        }
+        let (level, line) = self.current.get();
+        let mut seen = self.seen.borrow_mut();
+        let scope = if self.config.siblings_only {
+            // A heading starts a new parent for every deeper level
+            for deeper in &mut seen[level + 1..] {
+                deeper.clear();
+            }
+            &mut seen[level]
+        } else {
+            &mut seen[0]
+        };
+        match scope.get(&key) {
+            Some(&first) => Some(first),
+            None => {
+                scope.insert(key, line);
+                None
+            }
+        }
+    }
+}
+
+impl Rule for NoDuplicateHeadingRule {
+    fn id(&self) -> &'static str {
+        "MD024"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Duplicate Heading"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures headings in a document have different text."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-duplicate-heading", "no-duplicate-header"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings with the same text as an earlier heading. Renderers derive
+anchors from heading text, so links to the second heading go to the first
+one, and tables of contents show entries that cannot be told apart. The
+message gives the line of the first occurrence.
+
+Set `siblings_only = true` under `[rules.md024]` to only compare headings
+under the same parent heading, which allows a `### Examples` under every
+`## Command`. `ignore_case` and `ignore_punctuation` compare the text without
+case or ASCII punctuation.
+
+Bad:
+
+```markdown
+# Changelog
+
+## Fixed
+
+## Fixed
+```
+
+Good:
+
+```markdown
+# Changelog
+
+## 1.1
+
+### Fixed
+
+## 1.0
+
+### Fixed
+```
+
+The good example is still reported unless `siblings_only = true`."#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.current
+                    .set((*level as usize, context.current_line_number));
+                self.heading.start();
+                None
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let text = self.heading.finish()?;
+                let first = self.duplicate(&text)?;
+                Some(LintError {
+                    file_path: context.file_path.to_path_buf(),
+                    line: 0,
+                    column: None,
+                    message: format!(
+                        "Duplicate heading '{}', first used on line {}",
+                        text.trim(),
+                        first
+                    ),
+                    rule_id: self.id().to_string(),
+                })
+            }
+            _ => {
+                self.heading.push(event);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+    use crate::linter::lint_with_config;
+    use std::path::Path;
+
+    fn problems(content: &str, md024: DuplicateHeadingConfig) -> Vec<(usize, String)> {
+        let rules = RulesConfig {
+            md024,
+            ..RulesConfig::default()
+        };
+        lint_with_config(content, Path::new("test.md"), &rules)
+            .into_iter()
+            .filter(|e| e.rule_id == "MD024")
+            .map(|e| (e.line, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let content = "# Changelog\n\n## 1.1\n\n### Fixed\n\n## 1.0\n\n### Fixed\n\n### Fixed\n";
+
+        assert_eq!(
+            problems(content, DuplicateHeadingConfig::default()),
+            vec![
+                (
+                    9,
+                    "Duplicate heading 'Fixed', first used on line 5".to_string()
+                ),
+                (
+                    11,
+                    "Duplicate heading 'Fixed', first used on line 5".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            problems(
+                content,
+                DuplicateHeadingConfig {
+                    siblings_only: true,
+                    ..DuplicateHeadingConfig::default()
+                }
+            ),
+            vec![(
+                11,
+                "Duplicate heading 'Fixed', first used on line 9".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ignore_case_and_punctuation() {
+        let content = "# Set up\n\n## `Set-up`\n\n## set up\n";
+
+        assert!(problems(content, DuplicateHeadingConfig::default()).is_empty());
+        assert_eq!(
+            problems(
+                content,
+                DuplicateHeadingConfig {
+                    ignore_case: true,
+                    ..DuplicateHeadingConfig::default()
+                }
+            ),
+            vec![(
+                5,
+                "Duplicate heading 'set up', first used on line 1".to_string()
+            )]
+        );
+        assert_eq!(
+            problems(
+                "# FAQ\n\n## F.A.Q.\n",
+                DuplicateHeadingConfig {
+                    ignore_punctuation: true,
+                    ..DuplicateHeadingConfig::default()
+                }
+            ),
+            vec![(
+                3,
+                "Duplicate heading 'F.A.Q.', first used on line 1".to_string()
+            )]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::common::{LintContext, LintError, Rule};
+use super::common::{LintContext, LintError, Rule, tags};
 use pulldown_cmark::{Event, Tag};
 
 pub struct NoEmptyLinksRule;
@@ -28,6 +28,31 @@ impl Rule for NoEmptyLinksRule {
         "Ensures that all links have a non-empty URL"
     }
 
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LINKS, tags::ACCESSIBILITY]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["MD042", "no-empty-links"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports links whose destination is empty, which usually means the URL was
+never filled in. Readers clicking such a link stay on the page.
+
+Bad:
+
+```markdown
+Read the [installation guide]() first.
+```
+
+Good:
+
+```markdown
+Read the [installation guide](docs/install.md) first.
+```"#
+    }
+
     fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
         if let Event::Start(Tag::Link { dest_url, .. }) = event
             && dest_url.is_empty()
@@ -35,6 +60,7 @@ impl Rule for NoEmptyLinksRule {
             return Some(LintError {
                 file_path: context.file_path.to_path_buf(),
                 line: 0,
+                column: None,
                 message: "Empty link URL found".to_string(),
                 rule_id: self.id().to_string(),
             });
@@ -1,7 +1,15 @@
-use super::common::{LintContext, LintError, Rule};
-use pulldown_cmark::Event;
+use super::common::{LintContext, LintError, Rule, tags};
+use super::html::{self, HtmlScan, HtmlState};
+use crate::config::NoHtmlConfig;
+use crate::suppression;
+use pulldown_cmark::{Event, Tag};
+use std::cell::Cell;
 
-pub struct NoHtmlRule;
+pub struct NoHtmlRule {
+    config: NoHtmlConfig,
+    /// Scanner state carried from one line of an HTML block to the next.
+    state: Cell<HtmlState>,
+}
 
 impl Default for NoHtmlRule {
     fn default() -> Self {
@@ -11,7 +19,39 @@ impl Default for NoHtmlRule {
 
 impl NoHtmlRule {
     pub fn new() -> Self {
-        Self
+        Self::with_config(NoHtmlConfig::default())
+    }
+
+    pub fn with_config(config: NoHtmlConfig) -> Self {
+        Self {
+            config,
+            state: Cell::new(HtmlState::Text),
+        }
+    }
+
+    fn is_reported(&self, element: &str) -> bool {
+        let listed = |list: &[String]| list.iter().any(|name| name.eq_ignore_ascii_case(element));
+        !listed(&self.config.allowed_elements)
+            && (self.config.forbidden_elements.is_empty()
+                || listed(&self.config.forbidden_elements))
+    }
+
+    fn report(&self, html: &str, scan: &HtmlScan) -> Option<String> {
+        let element = scan
+            .tags
+            .iter()
+            .find(|tag| !tag.closing && !tag.name.is_empty() && self.is_reported(&tag.name));
+        if let Some(tag) = element {
+            Some(format!("Raw HTML found in markdown: <{}>", tag.name))
+        } else if scan.comments > 0
+            && !self.config.allow_comments
+            // Suppression comments are md-check's own syntax, not content
+            && !suppression::is_directive(html)
+        {
+            Some("Raw HTML comment found in markdown".to_string())
+        } else {
+            None
+        }
     }
 }
 
@@ -28,15 +68,135 @@ impl Rule for NoHtmlRule {
         "Ensures that no raw HTML is used in markdown"
     }
 
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HTML]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["MD033", "no-inline-html"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports raw HTML elements, in blocks and inline, naming the first one on each
+line. HTML is rendered differently, or stripped, by many Markdown renderers,
+and it is harder to review than Markdown.
+
+Elements listed in `allowed_elements` under `[rules.no_html]`, such as
+`details`, `summary` or `br`, are accepted. If `forbidden_elements` is set,
+only those elements are reported. HTML comments are accepted unless
+`allow_comments = false`; md-check's own `<!-- md-check-disable... -->`
+comments are never reported.
+
+Bad:
+
+```markdown
+<div align="center">
+  <img src="logo.png">
+</div>
+```
+
+Good:
+
+```markdown
+![Logo](logo.png)
+```"#
+    }
+
     fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
-        match event {
-            Event::Html(_) => Some(LintError {
-                file_path: context.file_path.to_path_buf(),
-                line: 0,
-                message: "Raw HTML found in markdown".to_string(),
-                rule_id: self.id().to_string(),
-            }),
+        let message = match event {
+            Event::Start(Tag::HtmlBlock) => {
+                self.state.set(HtmlState::Text);
+                None
+            }
+            Event::Html(html) => {
+                let scan = html::scan(html, self.state.get());
+                self.state.set(scan.state);
+                self.report(html, &scan)
+            }
+            Event::InlineHtml(html) => self.report(html, &html::scan(html, HtmlState::Text)),
             _ => None,
-        }
+        }?;
+        Some(LintError {
+            file_path: context.file_path.to_path_buf(),
+            line: 0,
+            column: None,
+            message,
+            rule_id: self.id().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+    use crate::linter::lint_with_config;
+    use std::path::Path;
+
+    const CONTENT: &str = "\
+<details>
+<summary>More</summary>
+
+Text with <kbd>Ctrl</kbd> and <br>
+
+</details>
+
+<!--
+<div>commented out</div>
+-->
+";
+
+    fn problems(config: NoHtmlConfig) -> Vec<(usize, String)> {
+        let rules = RulesConfig {
+            no_html: config,
+            ..RulesConfig::default()
+        };
+        lint_with_config(CONTENT, Path::new("test.md"), &rules)
+            .into_iter()
+            .filter(|e| e.rule_id == "NO_HTML")
+            .map(|e| (e.line, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_tag_names_outside_comments() {
+        assert_eq!(
+            problems(NoHtmlConfig::default()),
+            vec![
+                (1, "Raw HTML found in markdown: <details>".to_string()),
+                (2, "Raw HTML found in markdown: <summary>".to_string()),
+                (4, "Raw HTML found in markdown: <kbd>".to_string()),
+                (4, "Raw HTML found in markdown: <br>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allowed_and_forbidden_elements() {
+        let allowed = NoHtmlConfig {
+            allowed_elements: vec!["details".to_string(), "SUMMARY".to_string()],
+            allow_comments: false,
+            ..NoHtmlConfig::default()
+        };
+        assert_eq!(
+            problems(allowed.clone()),
+            vec![
+                (4, "Raw HTML found in markdown: <kbd>".to_string()),
+                (4, "Raw HTML found in markdown: <br>".to_string()),
+                (8, "Raw HTML comment found in markdown".to_string()),
+            ]
+        );
+
+        let forbidden = NoHtmlConfig {
+            forbidden_elements: vec!["kbd".to_string()],
+            ..allowed
+        };
+        assert_eq!(
+            problems(forbidden),
+            vec![
+                (4, "Raw HTML found in markdown: <kbd>".to_string()),
+                (8, "Raw HTML comment found in markdown".to_string()),
+            ]
+        );
     }
 }
@@ -0,0 +1,130 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::AtxLine;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct NoMissingSpaceAtxRule;
+
+impl Default for NoMissingSpaceAtxRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoMissingSpaceAtxRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let kinds = classify_lines(content);
+        let mut problems = Vec::new();
+        for (idx, (line, _)) in lines_with_endings(content).enumerate() {
+            // Without the space the line is a paragraph, not a heading
+            if kinds.get(idx) != Some(&LineKind::Text) {
+                continue;
+            }
+            let Some(atx) = AtxLine::parse(line) else {
+                continue;
+            };
+            // Lines that end in hashes are left to MD020
+            if !atx.before.is_empty() || atx.text.is_empty() || !atx.close.is_empty() {
+                continue;
+            }
+            problems.push(SourceProblem {
+                line: idx,
+                column: fixes::column_at(line, atx.text_offset()),
+                message: "No space after hash on atx style heading".to_string(),
+                lines: idx..idx + 1,
+                replacement: vec![atx.rebuild(" ", atx.after)],
+            });
+        }
+        problems
+    }
+}
+
+impl Rule for NoMissingSpaceAtxRule {
+    fn id(&self) -> &'static str {
+        "MD018"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Missing Space ATX"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures there is a space between the hashes and the text of an ATX heading."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-missing-space-atx"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports lines that look like ATX headings but have no space after the
+hashes. Markdown requires the space, so such a line is rendered as a
+paragraph that starts with `#`. Write a hashtag or an issue number that
+starts a line as `\#` to keep it.
+
+`md-check fix` inserts the space.
+
+Bad:
+
+```markdown
+#Installation
+```
+
+Good:
+
+```markdown
+# Installation
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_missing_space() {
+        let content = "#Title\n\n##Usage\n\n```\n#include <stdio.h>\n```\n\n\\#hashtag\n";
+
+        assert_eq!(
+            positions(content, "MD018"),
+            vec![(1, Some(2)), (3, Some(3))]
+        );
+        assert_eq!(
+            NoMissingSpaceAtxRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            content
+                .replace("#Title", "# Title")
+                .replace("##Usage", "## Usage")
+        );
+    }
+}
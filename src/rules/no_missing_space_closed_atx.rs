@@ -0,0 +1,145 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::AtxLine;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct NoMissingSpaceClosedAtxRule;
+
+impl Default for NoMissingSpaceClosedAtxRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoMissingSpaceClosedAtxRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let kinds = classify_lines(content);
+        let mut problems = Vec::new();
+        for (idx, (line, _)) in lines_with_endings(content).enumerate() {
+            if !matches!(kinds.get(idx), Some(LineKind::Text | LineKind::Heading)) {
+                continue;
+            }
+            let Some(atx) = AtxLine::parse(line) else {
+                continue;
+            };
+            // Headings without text, `# #`, have nothing to space out
+            if atx.close.is_empty()
+                || atx.text.is_empty()
+                || !(atx.before.is_empty() || atx.after.is_empty())
+            {
+                continue;
+            }
+            let offset = if atx.before.is_empty() {
+                atx.text_offset()
+            } else {
+                atx.text_offset() + atx.text.len()
+            };
+            let before = if atx.before.is_empty() {
+                " "
+            } else {
+                atx.before
+            };
+            let after = if atx.after.is_empty() { " " } else { atx.after };
+            problems.push(SourceProblem {
+                line: idx,
+                column: fixes::column_at(line, offset),
+                message: "No space inside hashes on closed atx style heading".to_string(),
+                lines: idx..idx + 1,
+                replacement: vec![atx.rebuild(before, after)],
+            });
+        }
+        problems
+    }
+}
+
+impl Rule for NoMissingSpaceClosedAtxRule {
+    fn id(&self) -> &'static str {
+        "MD020"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Missing Space Closed ATX"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures there are spaces inside the hashes of a closed ATX heading."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-missing-space-closed-atx"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports closed ATX headings without a space after the opening hashes or
+before the closing ones. Without the first space the line is not a heading
+at all, and without the second the closing hashes become part of the text.
+A heading that really ends in `#`, such as `C#`, should escape it as `\#`.
+
+`md-check fix` inserts the missing spaces.
+
+Bad:
+
+```markdown
+#Installation#
+
+## Usage##
+```
+
+Good:
+
+```markdown
+# Installation #
+
+## Usage ##
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_missing_space_inside_hashes() {
+        let content = "#Title#\n\n## Usage##\n\n### C\\#\n\n#### Done ####\n";
+
+        assert_eq!(
+            positions(content, "MD020"),
+            vec![(1, Some(2)), (3, Some(9))]
+        );
+        assert_eq!(
+            NoMissingSpaceClosedAtxRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "# Title #\n\n## Usage ##\n\n### C\\#\n\n#### Done ####\n"
+        );
+    }
+}
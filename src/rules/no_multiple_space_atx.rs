@@ -0,0 +1,126 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::AtxLine;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct NoMultipleSpaceAtxRule;
+
+impl Default for NoMultipleSpaceAtxRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoMultipleSpaceAtxRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let kinds = classify_lines(content);
+        let mut problems = Vec::new();
+        for (idx, (line, _)) in lines_with_endings(content).enumerate() {
+            if kinds.get(idx) != Some(&LineKind::Heading) {
+                continue;
+            }
+            let Some(atx) = AtxLine::parse(line) else {
+                continue;
+            };
+            // Closed headings are left to MD021
+            if atx.before.chars().count() < 2 || atx.text.is_empty() || !atx.close.is_empty() {
+                continue;
+            }
+            problems.push(SourceProblem {
+                line: idx,
+                column: fixes::column_at(line, atx.indent.len() + atx.open.len() + 1),
+                message: "Multiple spaces after hash on atx style heading".to_string(),
+                lines: idx..idx + 1,
+                replacement: vec![atx.rebuild(" ", atx.after)],
+            });
+        }
+        problems
+    }
+}
+
+impl Rule for NoMultipleSpaceAtxRule {
+    fn id(&self) -> &'static str {
+        "MD019"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Multiple Space ATX"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures a single space separates the hashes and the text of an ATX heading."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-multiple-space-atx"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports ATX headings with more than one space or tab after the hashes. The
+extra whitespace is not rendered and makes headings harder to search for.
+The column points at the first extra space.
+
+`md-check fix` leaves a single space.
+
+Bad:
+
+```markdown
+##  Installation
+```
+
+Good:
+
+```markdown
+## Installation
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_multiple_spaces() {
+        let content = "#  Title\n\n## \tUsage\n\n### Details ###\n\n#   Closed   #\n";
+
+        assert_eq!(
+            positions(content, "MD019"),
+            vec![(1, Some(3)), (3, Some(4))]
+        );
+        assert_eq!(
+            NoMultipleSpaceAtxRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "# Title\n\n## Usage\n\n### Details ###\n\n#   Closed   #\n"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use super::common::{LineKind, LintError, Rule, classify_lines, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::AtxLine;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct NoMultipleSpaceClosedAtxRule;
+
+impl Default for NoMultipleSpaceClosedAtxRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoMultipleSpaceClosedAtxRule {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let kinds = classify_lines(content);
+        let mut problems = Vec::new();
+        for (idx, (line, _)) in lines_with_endings(content).enumerate() {
+            if kinds.get(idx) != Some(&LineKind::Heading) {
+                continue;
+            }
+            let Some(atx) = AtxLine::parse(line) else {
+                continue;
+            };
+            // Missing spaces are left to MD020, and headings without text have
+            // nothing to space out
+            if !atx.is_closed() || atx.before.is_empty() || atx.text.is_empty() {
+                continue;
+            }
+            let offset = if atx.before.chars().count() > 1 {
+                atx.indent.len() + atx.open.len() + 1
+            } else if atx.after.chars().count() > 1 {
+                atx.text_offset() + atx.text.len() + 1
+            } else {
+                continue;
+            };
+            problems.push(SourceProblem {
+                line: idx,
+                column: fixes::column_at(line, offset),
+                message: "Multiple spaces inside hashes on closed atx style heading".to_string(),
+                lines: idx..idx + 1,
+                replacement: vec![atx.rebuild(" ", " ")],
+            });
+        }
+        problems
+    }
+}
+
+impl Rule for NoMultipleSpaceClosedAtxRule {
+    fn id(&self) -> &'static str {
+        "MD021"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Multiple Space Closed ATX"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures single spaces inside the hashes of a closed ATX heading."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-multiple-space-closed-atx"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports closed ATX headings with more than one space or tab after the
+opening hashes or before the closing ones. The column points at the first
+extra space.
+
+`md-check fix` leaves a single space on each side.
+
+Bad:
+
+```markdown
+##  Usage  ##
+```
+
+Good:
+
+```markdown
+## Usage ##
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::positions;
+
+    #[test]
+    fn test_multiple_spaces_inside_hashes() {
+        let content = "#  Title #\n\n## Usage   ##\n\n### Done ###\n";
+
+        assert_eq!(
+            positions(content, "MD021"),
+            vec![(1, Some(3)), (3, Some(10))]
+        );
+        assert_eq!(
+            NoMultipleSpaceClosedAtxRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "# Title #\n\n## Usage ##\n\n### Done ###\n"
+        );
+    }
+}
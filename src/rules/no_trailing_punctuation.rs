@@ -0,0 +1,169 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::headings::{self, AtxLine, HeadingStyle};
+use crate::config::TrailingPunctuationConfig;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Matches text ending in an HTML entity such as `&copy;`, whose `;` is not
+/// punctuation.
+static ENTITY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(?:#\d+|#[xX][0-9a-fA-F]+|[A-Za-z][A-Za-z0-9]*);$").unwrap());
+
+pub struct NoTrailingPunctuationRule {
+    config: TrailingPunctuationConfig,
+}
+
+impl Default for NoTrailingPunctuationRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoTrailingPunctuationRule {
+    pub fn new() -> Self {
+        Self::with_config(TrailingPunctuationConfig::default())
+    }
+
+    pub fn with_config(config: TrailingPunctuationConfig) -> Self {
+        Self { config }
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let mut problems = Vec::new();
+        for heading in headings::source_headings(content) {
+            if !heading.top_level {
+                continue;
+            }
+            // The byte range of the heading text within its last text line
+            let (idx, start, text) = match heading.style {
+                HeadingStyle::Setext => {
+                    let idx = heading.last_line - 1;
+                    (idx, 0, lines[idx].trim_end())
+                }
+                _ => match AtxLine::parse(lines[heading.first_line]) {
+                    Some(atx) => (heading.first_line, atx.text_offset(), atx.text),
+                    None => continue,
+                },
+            };
+            let kept = text.trim_end_matches(|c| self.config.punctuation.contains(c));
+            if kept.len() == text.len() || kept.trim().is_empty() || ENTITY.is_match(text) {
+                continue;
+            }
+            let line = lines[idx];
+            let end = start + kept.len();
+            problems.push(SourceProblem {
+                line: idx,
+                column: fixes::column_at(line, end),
+                message: format!("Trailing punctuation in heading: '{}'", &text[kept.len()..]),
+                lines: idx..idx + 1,
+                replacement: vec![format!("{}{}", &line[..end], &line[start + text.len()..])],
+            });
+        }
+        problems
+    }
+}
+
+impl Rule for NoTrailingPunctuationRule {
+    fn id(&self) -> &'static str {
+        "MD026"
+    }
+
+    fn name(&self) -> &'static str {
+        "No Trailing Punctuation"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures headings do not end with punctuation."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["no-trailing-punctuation"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports headings that end with punctuation, which usually means a sentence
+was turned into a heading. The characters are set with `punctuation` under
+`[rules.md026]`; the default `.,;:!。，；：！` allows questions, and an
+empty string allows everything. A trailing HTML entity such as `&copy;` is
+not reported.
+
+`md-check fix` removes the punctuation.
+
+Bad:
+
+```markdown
+## Installation:
+```
+
+Good:
+
+```markdown
+## Installation
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_trailing_punctuation() {
+        let content =
+            "# Title.\n\n## Why? ##\n\nNotes:\n------\n\n### Q&amp;A &copy;\n\n### Done!! ###\n";
+
+        assert_eq!(
+            problems(content, "MD026"),
+            vec![
+                (
+                    1,
+                    Some(8),
+                    "Trailing punctuation in heading: '.'".to_string()
+                ),
+                (
+                    5,
+                    Some(6),
+                    "Trailing punctuation in heading: ':'".to_string()
+                ),
+                (
+                    10,
+                    Some(9),
+                    "Trailing punctuation in heading: '!!'".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            NoTrailingPunctuationRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "# Title\n\n## Why? ##\n\nNotes\n------\n\n### Q&amp;A &copy;\n\n### Done ###\n"
+        );
+    }
+}
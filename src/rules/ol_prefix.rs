@@ -0,0 +1,197 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists::{self, SourceList};
+use crate::config::{OrderedListPrefixConfig, OrderedListStyle};
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct OrderedListPrefixRule {
+    config: OrderedListPrefixConfig,
+}
+
+impl Default for OrderedListPrefixRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderedListPrefixRule {
+    pub fn new() -> Self {
+        Self::with_config(OrderedListPrefixConfig::default())
+    }
+
+    pub fn with_config(config: OrderedListPrefixConfig) -> Self {
+        Self { config }
+    }
+
+    /// Number the item at `index` of `list` should have.
+    fn expected(&self, list: &SourceList, index: usize) -> Option<u64> {
+        let first = list.items.first()?.number()?;
+        let ordered = first + index as u64;
+        Some(match self.config.style {
+            OrderedListStyle::One => 1,
+            OrderedListStyle::Zero => 0,
+            OrderedListStyle::Ordered => ordered,
+            OrderedListStyle::OneOrOrdered => {
+                match list.items.get(1).and_then(|item| item.number()) {
+                    Some(second) if second == first && first <= 1 => first,
+                    _ => ordered,
+                }
+            }
+        })
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let mut problems = Vec::new();
+        for list in lists::source_lists(content)
+            .iter()
+            .filter(|list| list.ordered)
+        {
+            for (index, item) in list.items.iter().enumerate() {
+                let (Some(found), Some(expected)) = (item.number(), self.expected(list, index))
+                else {
+                    continue;
+                };
+                if found == expected {
+                    continue;
+                }
+                let line = lines[item.line];
+                let delimiter = item.marker.len() - 1;
+                problems.push(SourceProblem {
+                    line: item.line,
+                    column: fixes::column_at(line, item.offset),
+                    message: format!(
+                        "Ordered list item prefix: expected {}, found {}",
+                        expected, found
+                    ),
+                    lines: item.line..item.line + 1,
+                    replacement: vec![format!(
+                        "{}{}{}",
+                        &line[..item.offset],
+                        expected,
+                        &line[item.offset + delimiter..]
+                    )],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for OrderedListPrefixRule {
+    fn id(&self) -> &'static str {
+        "MD029"
+    }
+
+    fn name(&self) -> &'static str {
+        "Ordered List Prefix"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures ordered list items are numbered in one style."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ol-prefix"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports ordered list items numbered against the list's style, set with
+`style` under `[rules.md029]`:
+
+- `"one"`: every item is `1.`
+- `"zero"`: every item is `0.`
+- `"ordered"`: items count up from the first one, such as `1.`, `2.`, `3.`
+  or `0.`, `1.`, `2.`
+- `"one_or_ordered"`, the default: `"one"` or `"zero"` if the first two
+  items have the same number, `"ordered"` otherwise
+
+Renderers number items themselves, so a wrong number in the source is
+misleading rather than visible.
+
+`md-check fix` renumbers the items.
+
+Bad:
+
+```markdown
+1. Install
+3. Configure
+```
+
+Good:
+
+```markdown
+1. Install
+2. Configure
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_one_or_ordered() {
+        let content = "1. a\n1. b\n2. c\n\nText\n\n3) d\n5) e\n\n0. f\n0. g\n";
+
+        assert_eq!(
+            problems(content, "MD029"),
+            vec![
+                (
+                    3,
+                    Some(1),
+                    "Ordered list item prefix: expected 1, found 2".to_string()
+                ),
+                (
+                    8,
+                    Some(1),
+                    "Ordered list item prefix: expected 4, found 5".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            OrderedListPrefixRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "1. a\n1. b\n1. c\n\nText\n\n3) d\n4) e\n\n0. f\n0. g\n"
+        );
+    }
+
+    #[test]
+    fn test_required_style() {
+        let rule = OrderedListPrefixRule::with_config(OrderedListPrefixConfig {
+            style: OrderedListStyle::Ordered,
+        });
+
+        assert_eq!(
+            rule.fix("1. a\n1. b\n   - c\n1. d\n", &Suppressions::default())
+                .unwrap(),
+            "1. a\n2. b\n   - c\n3. d\n"
+        );
+    }
+}
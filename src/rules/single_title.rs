@@ -0,0 +1,204 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use super::headings::{FrontMatter, HeadingText};
+use crate::config::{FrontMatterTitle, SingleTitleConfig};
+use pulldown_cmark::{Event, Tag, TagEnd};
+use std::cell::Cell;
+
+pub struct SingleTitleRule {
+    config: SingleTitleConfig,
+    front_matter: FrontMatter,
+    /// Whether a heading of the configured level was seen.
+    seen: Cell<bool>,
+    /// Text of the top-level heading, when it must match the title.
+    heading: HeadingText,
+}
+
+impl Default for SingleTitleRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SingleTitleRule {
+    pub fn new() -> Self {
+        Self::with_config(SingleTitleConfig::default())
+    }
+
+    pub fn with_config(config: SingleTitleConfig) -> Self {
+        Self {
+            config,
+            front_matter: FrontMatter::default(),
+            seen: Cell::new(false),
+            heading: HeadingText::default(),
+        }
+    }
+
+    fn problem(&self, event: &Event<'_>) -> Option<String> {
+        let title = match self.config.front_matter_title {
+            FrontMatterTitle::Ignore => None,
+            _ => self.front_matter.title(),
+        };
+        match event {
+            Event::Start(Tag::Heading { level, .. })
+                if *level as usize == self.config.level as usize =>
+            {
+                if self.seen.replace(true) {
+                    return Some("Multiple top-level headings in the same document".to_string());
+                }
+                match (self.config.front_matter_title, title) {
+                    (FrontMatterTitle::Heading, Some(_)) => Some(
+                        "Top-level heading repeats the title already set in front matter"
+                            .to_string(),
+                    ),
+                    (FrontMatterTitle::Match, Some(_)) => {
+                        self.heading.start();
+                        None
+                    }
+                    _ => None,
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let text = self.heading.finish()?;
+                let title = title?;
+                let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+                (normalize(&text) != normalize(&title)).then(|| {
+                    format!(
+                        "Top-level heading '{}' does not match front matter title '{}'",
+                        text.trim(),
+                        title
+                    )
+                })
+            }
+            _ => {
+                self.heading.push(event);
+                None
+            }
+        }
+    }
+}
+
+impl Rule for SingleTitleRule {
+    fn id(&self) -> &'static str {
+        "MD025"
+    }
+
+    fn name(&self) -> &'static str {
+        "Single Title"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures a document has only one top-level heading."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HEADINGS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["single-title", "single-h1"]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports every top-level heading after the first. The top-level heading is the
+document's title, and a second one usually means the levels below it are
+off by one. The level is set with `level` under `[rules.md025]`.
+
+A `title` in YAML or TOML front matter acts as the title too, so a heading
+of that level is reported as a second one. With
+`front_matter_title = "match"` the heading must repeat the front matter
+title instead, and `"ignore"` disregards front matter.
+
+Bad:
+
+```markdown
+# Installation
+
+# Usage
+```
+
+Good:
+
+```markdown
+# Guide
+
+## Installation
+
+## Usage
+```"#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        if self.front_matter.observe(event) {
+            return None;
+        }
+        self.problem(event).map(|message| LintError {
+            file_path: context.file_path.to_path_buf(),
+            line: 0,
+            column: None,
+            message,
+            rule_id: self.id().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RulesConfig;
+    use crate::linter::lint_with_config;
+    use std::path::Path;
+
+    fn problems(content: &str, front_matter_title: FrontMatterTitle) -> Vec<(usize, String)> {
+        let rules = RulesConfig {
+            md025: SingleTitleConfig {
+                front_matter_title,
+                ..SingleTitleConfig::default()
+            },
+            ..RulesConfig::default()
+        };
+        lint_with_config(content, Path::new("test.md"), &rules)
+            .into_iter()
+            .filter(|e| e.rule_id == "MD025")
+            .map(|e| (e.line, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_second_top_level_heading() {
+        let content = "# Guide\n\n## Install\n\n# Usage\n";
+
+        assert_eq!(
+            problems(content, FrontMatterTitle::Heading),
+            vec![(
+                5,
+                "Multiple top-level headings in the same document".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_front_matter_title() {
+        let content = "---\ntitle: \"Getting started\"\n---\n\n# Getting  started\n";
+
+        assert_eq!(
+            problems(content, FrontMatterTitle::Heading),
+            vec![(
+                5,
+                "Top-level heading repeats the title already set in front matter".to_string()
+            )]
+        );
+        assert!(problems(content, FrontMatterTitle::Match).is_empty());
+        assert_eq!(
+            problems(
+                &content.replace("# Getting", "# `Quick`"),
+                FrontMatterTitle::Match
+            ),
+            vec![(
+                5,
+                "Top-level heading 'Quick  started' does not match front matter title 'Getting started'"
+                    .to_string()
+            )]
+        );
+        assert!(problems(&content.replace("title", "layout"), FrontMatterTitle::Match).is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists;
+use crate::config::UnorderedListIndentConfig;
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+pub struct UnorderedListIndentRule {
+    config: UnorderedListIndentConfig,
+}
+
+impl Default for UnorderedListIndentRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnorderedListIndentRule {
+    pub fn new() -> Self {
+        Self::with_config(UnorderedListIndentConfig::default())
+    }
+
+    pub fn with_config(config: UnorderedListIndentConfig) -> Self {
+        Self { config }
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let lists = lists::source_lists(content);
+        let mut problems = Vec::new();
+        for list in &lists {
+            // Under an ordered item the indentation depends on the number's width
+            if list.ordered || list.quoted || list.parents.iter().any(|&p| lists[p].ordered) {
+                continue;
+            }
+            let expected = list.parents.len() * self.config.indent;
+            for item in &list.items {
+                let line = lines[item.line];
+                let indent = &line[..item.offset];
+                let found = indent.chars().count();
+                if found == expected || !indent.trim().is_empty() {
+                    continue;
+                }
+                problems.push(SourceProblem {
+                    line: item.line,
+                    column: fixes::column_at(line, item.offset),
+                    message: format!(
+                        "Unordered list indentation: expected {} spaces, found {}",
+                        expected, found
+                    ),
+                    lines: item.line..item.line + 1,
+                    replacement: vec![format!("{}{}", " ".repeat(expected), &line[item.offset..])],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for UnorderedListIndentRule {
+    fn id(&self) -> &'static str {
+        "MD007"
+    }
+
+    fn name(&self) -> &'static str {
+        "Unordered List Indent"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures nested unordered lists are indented by a fixed number of spaces per level."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS, tags::WHITESPACE]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ul-indent"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports unordered list items that are not indented by `indent` spaces per
+nesting level, 2 by default, set under `[rules.md007]`. Top-level lists start
+in the first column. Lists nested in ordered lists or block quotes are not
+checked, since their indentation depends on what contains them.
+
+`md-check fix` re-indents the items.
+
+Bad:
+
+```markdown
+- Install
+    - Download
+```
+
+Good:
+
+```markdown
+- Install
+  - Download
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_nested_indentation() {
+        let content = " - a\n    - b\n      - c\n\n1. d\n   - e\n";
+
+        assert_eq!(
+            problems(content, "MD007"),
+            vec![
+                (
+                    1,
+                    Some(2),
+                    "Unordered list indentation: expected 0 spaces, found 1".to_string()
+                ),
+                (
+                    2,
+                    Some(5),
+                    "Unordered list indentation: expected 2 spaces, found 4".to_string()
+                ),
+                (
+                    3,
+                    Some(7),
+                    "Unordered list indentation: expected 4 spaces, found 6".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            UnorderedListIndentRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "- a\n  - b\n    - c\n\n1. d\n   - e\n"
+        );
+        let rule = UnorderedListIndentRule::with_config(UnorderedListIndentConfig { indent: 4 });
+        assert_eq!(
+            rule.fix("- a\n  - b\n", &Suppressions::default()).unwrap(),
+            "- a\n    - b\n"
+        );
+    }
+}
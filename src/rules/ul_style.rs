@@ -0,0 +1,203 @@
+use super::common::{LintError, Rule, tags};
+use super::fixes::{self, SourceProblem};
+use super::lists;
+use crate::config::{UnorderedListStyle, UnorderedListStyleConfig};
+use crate::suppression::Suppressions;
+use crate::text::lines_with_endings;
+use std::path::Path;
+
+const MARKERS: [char; 3] = ['-', '*', '+'];
+
+pub struct UnorderedListStyleRule {
+    config: UnorderedListStyleConfig,
+}
+
+impl Default for UnorderedListStyleRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnorderedListStyleRule {
+    pub fn new() -> Self {
+        Self::with_config(UnorderedListStyleConfig::default())
+    }
+
+    pub fn with_config(config: UnorderedListStyleConfig) -> Self {
+        Self { config }
+    }
+
+    fn problems(&self, content: &str) -> Vec<SourceProblem> {
+        let lines: Vec<_> = lines_with_endings(content).map(|(line, _)| line).collect();
+        let lists = lists::source_lists(content);
+        let mut first = None;
+        // Marker of each nesting level, for the sublist style
+        let mut levels: Vec<char> = Vec::new();
+        let mut problems = Vec::new();
+        for list in lists.iter().filter(|list| !list.ordered) {
+            let depth = list.parents.iter().filter(|&&p| !lists[p].ordered).count();
+            for item in &list.items {
+                let marker = item.marker.chars().next().unwrap_or('-');
+                let expected = match self.config.style {
+                    UnorderedListStyle::Consistent => *first.get_or_insert(marker),
+                    UnorderedListStyle::Asterisk => '*',
+                    UnorderedListStyle::Dash => '-',
+                    UnorderedListStyle::Plus => '+',
+                    UnorderedListStyle::Sublist => {
+                        if levels.len() == depth {
+                            let parent = depth.checked_sub(1).and_then(|p| levels.get(p));
+                            let own = match parent {
+                                Some(&parent) if parent == marker => {
+                                    let next =
+                                        MARKERS.iter().position(|&m| m == parent).unwrap_or(0);
+                                    MARKERS[(next + 1) % MARKERS.len()]
+                                }
+                                _ => marker,
+                            };
+                            levels.push(own);
+                        }
+                        levels[depth.min(levels.len() - 1)]
+                    }
+                };
+                if marker == expected {
+                    continue;
+                }
+                let line = lines[item.line];
+                problems.push(SourceProblem {
+                    line: item.line,
+                    column: fixes::column_at(line, item.offset),
+                    message: format!(
+                        "Unordered list marker should be '{}', found '{}'",
+                        expected, marker
+                    ),
+                    lines: item.line..item.line + 1,
+                    replacement: vec![format!(
+                        "{}{}{}",
+                        &line[..item.offset],
+                        expected,
+                        &line[item.offset + 1..]
+                    )],
+                });
+            }
+        }
+        problems
+    }
+}
+
+impl Rule for UnorderedListStyleRule {
+    fn id(&self) -> &'static str {
+        "MD004"
+    }
+
+    fn name(&self) -> &'static str {
+        "Unordered List Style"
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures unordered lists use the same marker: dash, asterisk or plus."
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LISTS]
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ul-style"]
+    }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports unordered list items whose marker differs from the others. With the
+default `style = "consistent"` every item must use the marker of the first
+one in the document; `"dash"`, `"asterisk"` and `"plus"` require a marker,
+and `"sublist"` gives each nesting level its own marker, different from its
+parent's. The style is set under `[rules.md004]`. Changing the marker in the
+middle of a list starts a new list.
+
+`md-check fix` replaces the markers.
+
+Bad:
+
+```markdown
+- Install
+* Configure
+```
+
+Good:
+
+```markdown
+- Install
+- Configure
+```"#
+    }
+
+    fn check_document(&self, content: &str, file_path: &Path) -> Vec<LintError> {
+        fixes::report(self.id(), file_path, self.problems(content))
+    }
+
+    fn fix(&self, content: &str, suppressions: &Suppressions) -> Option<String> {
+        Some(fixes::apply(
+            self,
+            content,
+            suppressions,
+            self.problems(content),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::problems;
+
+    #[test]
+    fn test_consistent_markers() {
+        let content = "- a\n  * b\n+ c\n\n> * d\n\n1. e\n";
+
+        assert_eq!(
+            problems(content, "MD004"),
+            vec![
+                (
+                    2,
+                    Some(3),
+                    "Unordered list marker should be '-', found '*'".to_string()
+                ),
+                (
+                    3,
+                    Some(1),
+                    "Unordered list marker should be '-', found '+'".to_string()
+                ),
+                (
+                    5,
+                    Some(3),
+                    "Unordered list marker should be '-', found '*'".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            UnorderedListStyleRule::new()
+                .fix(content, &Suppressions::default())
+                .unwrap(),
+            "- a\n  - b\n- c\n\n> - d\n\n1. e\n"
+        );
+    }
+
+    #[test]
+    fn test_sublist_markers() {
+        let rule = UnorderedListStyleRule::with_config(UnorderedListStyleConfig {
+            style: UnorderedListStyle::Sublist,
+        });
+
+        assert_eq!(
+            rule.fix(
+                "- a\n  - b\n    + c\n- d\n  * e\n",
+                &Suppressions::default()
+            )
+            .unwrap(),
+            "- a\n  * b\n    + c\n- d\n  * e\n"
+        );
+    }
+}
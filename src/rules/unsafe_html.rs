@@ -0,0 +1,172 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use super::html::{self, HtmlScan, HtmlState};
+use pulldown_cmark::{Event, Tag};
+use std::cell::Cell;
+
+pub struct UnsafeHtmlRule {
+    /// Scanner state carried from one line of an HTML block to the next.
+    state: Cell<HtmlState>,
+}
+
+impl Default for UnsafeHtmlRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UnsafeHtmlRule {
+    pub fn new() -> Self {
+        Self {
+            state: Cell::new(HtmlState::Text),
+        }
+    }
+}
+
+/// Whether `url` uses the `javascript:` scheme, which browsers accept with
+/// any case and with whitespace or control characters mixed in.
+fn is_javascript_url(url: &str) -> bool {
+    let scheme: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take("javascript:".len())
+        .collect();
+    scheme.eq_ignore_ascii_case("javascript:")
+}
+
+/// Describes the first dangerous construct in `scan`.
+fn find_unsafe(scan: &HtmlScan) -> Option<String> {
+    for tag in scan.tags.iter().filter(|tag| !tag.closing) {
+        if tag.name == "script" {
+            return Some("Unsafe HTML: <script> element".to_string());
+        }
+        for (attribute, value) in &tag.attributes {
+            if attribute.len() > 2 && attribute.starts_with("on") {
+                return Some(format!(
+                    "Unsafe HTML: event handler attribute `{}`",
+                    attribute
+                ));
+            }
+            if is_javascript_url(value) {
+                return Some(format!(
+                    "Unsafe HTML: javascript: URL in `{}` attribute",
+                    attribute
+                ));
+            }
+        }
+    }
+    None
+}
+
+impl Rule for UnsafeHtmlRule {
+    fn id(&self) -> &'static str {
+        "UNSAFE_HTML"
+    }
+
+    fn name(&self) -> &'static str {
+        "Unsafe HTML"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags HTML and links that can run scripts: <script>, on* attributes and javascript: URLs"
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::HTML, tags::SECURITY]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports constructs that run code when a document is rendered as HTML:
+`<script>` elements, event handler attributes such as `onclick` or
+`onerror`, and `javascript:` URLs in attributes or Markdown links. Most
+renderers strip them, but any that does not turns the document into an
+attack vector. Unlike `NO_HTML`, this rule is not affected by
+`allowed_elements`.
+
+Bad:
+
+```markdown
+<img src="logo.png" onerror="alert(1)">
+
+[Click me](javascript:alert(1))
+```
+
+Good:
+
+```markdown
+<img src="logo.png" alt="Logo">
+
+[Click me](https://example.com)
+```"#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        let message = match event {
+            Event::Start(Tag::HtmlBlock) => {
+                self.state.set(HtmlState::Text);
+                None
+            }
+            Event::Html(html) => {
+                let scan = html::scan(html, self.state.get());
+                self.state.set(scan.state);
+                find_unsafe(&scan)
+            }
+            Event::InlineHtml(html) => find_unsafe(&html::scan(html, HtmlState::Text)),
+            Event::Start(Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. })
+                if is_javascript_url(dest_url) =>
+            {
+                Some("Unsafe link: javascript: URL".to_string())
+            }
+            _ => None,
+        }?;
+        Some(LintError {
+            file_path: context.file_path.to_path_buf(),
+            line: 0,
+            column: None,
+            message,
+            rule_id: self.id().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linter::lint;
+    use std::path::Path;
+
+    #[test]
+    fn test_unsafe_constructs() {
+        let content = "\
+<div>
+<img src=\"x.png\"
+     onerror=\"alert(1)\">
+</div>
+
+Text <a href=\" JavaScript:alert(1)\">a</a> and [b](javascript:void(0)).
+
+<script>alert(1)</script>
+
+<!-- <script>commented out</script> -->
+";
+        let errors: Vec<_> = lint(content, Path::new("test.md"))
+            .into_iter()
+            .filter(|e| e.rule_id == "UNSAFE_HTML")
+            .map(|e| (e.line, e.message))
+            .collect();
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    3,
+                    "Unsafe HTML: event handler attribute `onerror`".to_string()
+                ),
+                (
+                    6,
+                    "Unsafe HTML: javascript: URL in `href` attribute".to_string()
+                ),
+                (6, "Unsafe link: javascript: URL".to_string()),
+                (8, "Unsafe HTML: <script> element".to_string()),
+            ]
+        );
+    }
+}
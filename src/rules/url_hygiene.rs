@@ -0,0 +1,251 @@
+use super::common::{LintContext, LintError, Rule, tags};
+use crate::config::UrlHygieneConfig;
+use pulldown_cmark::{Event, Tag};
+use std::net::IpAddr;
+use url::{Host, Url};
+
+/// Query parameters added by analytics and ad platforms.
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_ga",
+    "_gl",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "oly_enc_id",
+    "oly_anon_id",
+    "vero_id",
+];
+
+pub struct UrlHygieneRule {
+    config: UrlHygieneConfig,
+}
+
+impl Default for UrlHygieneRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UrlHygieneRule {
+    pub fn new() -> Self {
+        Self::with_config(UrlHygieneConfig::default())
+    }
+
+    pub fn with_config(config: UrlHygieneConfig) -> Self {
+        Self { config }
+    }
+
+    fn find_problem(&self, url: &str) -> Option<String> {
+        if self.config.nested_markdown && has_markdown_syntax(url) {
+            return Some(format!("URL contains Markdown syntax: {}", url));
+        }
+
+        if self.config.whitespace && url.contains(char::is_whitespace) {
+            return Some(format!("URL contains whitespace: {}", url));
+        }
+
+        if self.config.trailing_punctuation {
+            if let Some(c) = url
+                .chars()
+                .last()
+                .filter(|c| matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"'))
+            {
+                return Some(format!("URL ends with '{}': {}", c, url));
+            }
+            if url.matches('(').count() != url.matches(')').count() {
+                return Some(format!("URL has unbalanced parentheses: {}", url));
+            }
+        }
+
+        // The remaining checks only apply to URLs that parse
+        let parsed = Url::parse(url).ok()?;
+
+        if self.config.local_addresses
+            && let Some(host) = parsed.host()
+            && is_local_host(&host)
+        {
+            return Some(format!("URL points to a local or private address: {}", url));
+        }
+
+        if self.config.tracking_params
+            && let Some(param) = parsed
+                .query_pairs()
+                .map(|(name, _)| name.to_ascii_lowercase())
+                .find(|name| self.is_tracking_param(name))
+        {
+            return Some(format!(
+                "URL contains tracking parameter '{}': {}",
+                param, url
+            ));
+        }
+
+        None
+    }
+
+    fn is_tracking_param(&self, name: &str) -> bool {
+        name.starts_with("utm_")
+            || TRACKING_PARAMS.contains(&name)
+            || self
+                .config
+                .extra_tracking_params
+                .iter()
+                .any(|extra| extra.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Detects leftovers such as `[text](url)` pasted into a link destination.
+fn has_markdown_syntax(url: &str) -> bool {
+    url.contains("](")
+        || url.starts_with('[')
+        || url.starts_with('<')
+        || url.ends_with('>')
+        || url.starts_with('`')
+        || url.ends_with('`')
+        || url.contains("%5D(")
+}
+
+fn is_local_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Host::Ipv4(ip) => is_local_ip(&IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => is_local_ip(&IpAddr::V6(*ip)),
+    }
+}
+
+fn is_local_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+                || ip
+                    .to_ipv4_mapped()
+                    .is_some_and(|v4| is_local_ip(&IpAddr::V4(v4)))
+        }
+    }
+}
+
+impl Rule for UrlHygieneRule {
+    fn id(&self) -> &'static str {
+        "URL_HYGIENE"
+    }
+
+    fn name(&self) -> &'static str {
+        "URL Hygiene"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flags suspicious link URLs: trailing punctuation, Markdown leftovers, whitespace, local addresses and tracking parameters"
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &[tags::LINKS]
+    }
+
+    fn explanation(&self) -> &'static str {
+        r#"Reports link URLs that are probably not what the author meant:
+
+- trailing punctuation such as `.` or an unbalanced `)` picked up from the
+  surrounding sentence;
+- Markdown syntax such as `[text](url)` pasted into the destination;
+- spaces or line breaks inside the URL, which should be `%20`;
+- `localhost`, loopback and private network addresses;
+- `http://` links whose `https://` variant works, which needs link checking
+  and an extra request per link, so it is off unless `insecure_http = true`;
+- tracking parameters such as `utm_source` or `fbclid`.
+
+Each category can be turned on or off under `[rules.url_hygiene]`.
+
+Bad:
+
+```markdown
+See <https://example.com/docs.> and [the API](http://localhost:8080/api).
+Shared from [a post](https://example.com/post?utm_source=newsletter).
+```
+
+Good:
+
+```markdown
+See <https://example.com/docs> and [the API](https://api.example.com).
+Shared from [a post](https://example.com/post).
+```"#
+    }
+
+    fn check(&self, event: &Event<'_>, context: &LintContext) -> Option<LintError> {
+        if let Event::Start(Tag::Link { dest_url, .. }) = event
+            && let Some(message) = self.find_problem(dest_url)
+        {
+            return Some(LintError {
+                file_path: context.file_path.to_path_buf(),
+                line: 0,
+                column: None,
+                message,
+                rule_id: self.id().to_string(),
+            });
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_problem_categories() {
+        let rule = UrlHygieneRule::new();
+
+        assert!(rule.find_problem("https://example.com/docs").is_none());
+        assert!(
+            rule.find_problem("https://en.wikipedia.org/wiki/Rust_(programming_language)")
+                .is_none()
+        );
+        assert!(rule.find_problem("https://example.com/a,").is_some());
+        assert!(rule.find_problem("https://example.com/a_(b").is_some());
+        assert!(
+            rule.find_problem("[https://x.org](https://x.org)")
+                .is_some()
+        );
+        assert_eq!(
+            rule.find_problem("https://example.com/a b").as_deref(),
+            Some("URL contains whitespace: https://example.com/a b")
+        );
+        assert!(rule.find_problem("https://example.com/a%20b").is_none());
+        assert!(rule.find_problem("http://localhost:3000").is_some());
+        assert!(rule.find_problem("http://127.0.0.1/").is_some());
+        assert!(rule.find_problem("http://[::1]/").is_some());
+        assert!(rule.find_problem("http://10.1.2.3/").is_some());
+        assert!(
+            rule.find_problem("https://example.com/?fbclid=abc")
+                .is_some()
+        );
+        assert!(rule.find_problem("https://example.com/?page=2").is_none());
+    }
+
+    #[test]
+    fn test_extra_tracking_params() {
+        let rule = UrlHygieneRule::with_config(UrlHygieneConfig {
+            extra_tracking_params: vec!["ref".to_string()],
+            ..UrlHygieneConfig::default()
+        });
+
+        assert!(rule.find_problem("https://example.com/?ref=home").is_some());
+    }
+}
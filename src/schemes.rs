@@ -0,0 +1,257 @@
+use percent_encoding::percent_decode_str;
+
+/// URL schemes accepted when no allowlist is configured.
+pub const DEFAULT_ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ftp"];
+
+/// Validates a link against the allowed schemes and the syntax rules of
+/// `mailto:` and `tel:`.
+///
+/// `scheme` must already be lowercase, as returned by `Url::scheme`.
+/// Returns a human-readable error for invalid links.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::schemes::{validate_scheme_link, DEFAULT_ALLOWED_SCHEMES};
+///
+/// let allowed: Vec<String> = DEFAULT_ALLOWED_SCHEMES.iter().map(|s| s.to_string()).collect();
+///
+/// assert!(validate_scheme_link("mailto", "mailto:support@example.com", &allowed).is_ok());
+/// assert!(validate_scheme_link("mailto", "mailto:support@example", &allowed).is_err());
+///
+/// let error = validate_scheme_link("htps", "htps://example.com", &allowed).unwrap_err();
+/// assert_eq!(error, "Unknown URL scheme 'htps' (did you mean 'https'?)");
+/// ```
+pub fn validate_scheme_link(scheme: &str, url: &str, allowed: &[String]) -> Result<(), String> {
+    if !allowed.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+        return Err(match suggest_scheme(scheme, allowed) {
+            Some(suggestion) => format!(
+                "Unknown URL scheme '{}' (did you mean '{}'?)",
+                scheme, suggestion
+            ),
+            None => format!("URL scheme '{}' is not allowed", scheme),
+        });
+    }
+
+    let rest = &url[scheme.len() + 1..];
+    match scheme {
+        "mailto" => validate_mailto(rest),
+        "tel" => validate_tel(rest),
+        _ => Ok(()),
+    }
+}
+
+/// Validates the part of a `mailto:` URL after the scheme (RFC 6068).
+fn validate_mailto(rest: &str) -> Result<(), String> {
+    let (addresses, query) = match rest.split_once('?') {
+        Some((addresses, query)) => (addresses, Some(query)),
+        None => (rest, None),
+    };
+
+    let addresses = percent_decode_str(addresses).decode_utf8_lossy();
+    if addresses.trim().is_empty() {
+        let has_to = query.is_some_and(|q| {
+            q.split('&')
+                .any(|param| param.to_ascii_lowercase().starts_with("to="))
+        });
+        return if has_to {
+            Ok(())
+        } else {
+            Err("mailto: link has no address".to_string())
+        };
+    }
+
+    for address in addresses.split(',') {
+        validate_email(address.trim())?;
+    }
+    Ok(())
+}
+
+/// Validates a single e-mail address in `local@domain` form.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::schemes::validate_email;
+///
+/// assert!(validate_email("first.last+tag@docs.example.org").is_ok());
+/// assert!(validate_email("first..last@example.org").is_err());
+/// assert!(validate_email("support@localhost").is_err());
+/// ```
+pub fn validate_email(address: &str) -> Result<(), String> {
+    let invalid = |reason: &str| Err(format!("Invalid e-mail address '{}': {}", address, reason));
+
+    let Some((local, domain)) = address.rsplit_once('@') else {
+        return invalid("missing '@'");
+    };
+
+    if local.is_empty() || local.len() > 64 {
+        return invalid("local part must be 1-64 characters");
+    }
+    let quoted = local.len() >= 2 && local.starts_with('"') && local.ends_with('"');
+    if !quoted {
+        if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+            return invalid("misplaced '.' in local part");
+        }
+        if let Some(c) = local
+            .chars()
+            .find(|&c| !(c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c)))
+        {
+            return invalid(&format!("invalid character '{}' in local part", c));
+        }
+    }
+
+    if domain.starts_with('[') && domain.ends_with(']') {
+        return Ok(());
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return invalid("domain has no top-level domain");
+    }
+    for label in &labels {
+        let valid = !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-');
+        if !valid {
+            return invalid(&format!("invalid domain label '{}'", label));
+        }
+    }
+    let tld = labels[labels.len() - 1];
+    if tld.len() < 2 || !tld.chars().all(char::is_alphabetic) {
+        return invalid(&format!("invalid top-level domain '{}'", tld));
+    }
+    Ok(())
+}
+
+/// Validates the part of a `tel:` URL after the scheme.
+///
+/// Global numbers must look like E.164: a `+`, a non-zero country code and
+/// 7 to 15 digits in total, optionally split by `-`, `.`, spaces or
+/// parentheses. Local numbers need a `phone-context` parameter (RFC 3966).
+fn validate_tel(rest: &str) -> Result<(), String> {
+    let decoded = percent_decode_str(rest).decode_utf8_lossy();
+    let mut parts = decoded.split(';');
+    let number = parts.next().unwrap_or_default().trim();
+    let has_context = parts.any(|param| {
+        param
+            .trim()
+            .to_ascii_lowercase()
+            .starts_with("phone-context=")
+    });
+
+    let invalid = |reason: &str| Err(format!("Invalid phone number '{}': {}", number, reason));
+
+    let (global, digits_part) = match number.strip_prefix('+') {
+        Some(digits) => (true, digits),
+        None => (false, number),
+    };
+    if let Some(c) = digits_part
+        .chars()
+        .find(|&c| !(c.is_ascii_digit() || "-. ()".contains(c)))
+    {
+        return invalid(&format!("unexpected character '{}'", c));
+    }
+
+    let digits: String = digits_part.chars().filter(char::is_ascii_digit).collect();
+    if global {
+        if digits.starts_with('0') {
+            return invalid("country code cannot start with 0");
+        }
+        if !(7..=15).contains(&digits.len()) {
+            return invalid("international numbers have 7 to 15 digits");
+        }
+    } else if digits.is_empty() {
+        return invalid("no digits");
+    } else if !has_context {
+        return invalid("use international format (+<country code>...) or add ;phone-context=");
+    }
+    Ok(())
+}
+
+/// Suggests an allowed scheme that is at most two edits away from `scheme`.
+fn suggest_scheme<'a>(scheme: &str, allowed: &'a [String]) -> Option<&'a str> {
+    allowed
+        .iter()
+        .map(|candidate| (edit_distance(scheme, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed() -> Vec<String> {
+        DEFAULT_ALLOWED_SCHEMES
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_mailto_addresses() {
+        let allowed = allowed();
+        let check = |url: &str| validate_scheme_link("mailto", url, &allowed);
+
+        assert!(check("mailto:a@example.com,b@example.org?subject=Hi").is_ok());
+        assert!(check("mailto:%22john%20doe%22@example.com").is_ok());
+        assert!(check("mailto:?to=a@example.com").is_ok());
+        assert!(check("mailto:").is_err());
+        assert!(check("mailto:support@example").is_err());
+        assert!(check("mailto:support.example.com").is_err());
+        assert!(check("mailto:sup port@example.com").is_err());
+        assert!(check("mailto:support@-example.com").is_err());
+    }
+
+    #[test]
+    fn test_tel_numbers() {
+        let allowed = allowed();
+        let check = |url: &str| validate_scheme_link("tel", url, &allowed);
+
+        assert!(check("tel:+1-201-555-0123").is_ok());
+        assert!(check("tel:+44%2020%207946%200958").is_ok());
+        assert!(check("tel:+49(0)30.1234567;ext=12").is_ok());
+        assert!(check("tel:7042;phone-context=example.com").is_ok());
+        assert!(check("tel:555-0123").is_err());
+        assert!(check("tel:+0123456789").is_err());
+        assert!(check("tel:+12").is_err());
+        assert!(check("tel:+1-800-FLOWERS").is_err());
+    }
+
+    #[test]
+    fn test_scheme_allowlist_and_suggestions() {
+        let allowed = allowed();
+
+        assert_eq!(
+            validate_scheme_link("htp", "htp://example.com", &allowed),
+            Err("Unknown URL scheme 'htp' (did you mean 'http'?)".to_string())
+        );
+        assert_eq!(
+            validate_scheme_link("mialto", "mialto:a@example.com", &allowed),
+            Err("Unknown URL scheme 'mialto' (did you mean 'mailto'?)".to_string())
+        );
+        assert_eq!(
+            validate_scheme_link("slack", "slack://open", &allowed),
+            Err("URL scheme 'slack' is not allowed".to_string())
+        );
+        assert!(validate_scheme_link("ftp", "ftp://ftp.example.com/file", &allowed).is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use crate::rules::common::PARSER_OPTIONS;
+use crate::text::{LineIndex, lines_with_endings};
+use pulldown_cmark::{Event, Parser};
+
+/// Matches `<!-- md-check-disable-next-line MD012 NO_HTML -->`, the other
+/// forms, and their `markdownlint-` equivalents.
+static DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"<!--\s*(?:md-check|markdownlint)-(disable-next-line|disable-line|disable-file|enable-file|disable|enable)((?:[\s,]+[A-Za-z0-9_-]+)*)\s*-->",
+    )
+    .unwrap()
+});
+
+/// Returns the comment that disables `rule_id` on the line it ends.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::suppression::disable_line_comment;
+///
+/// assert_eq!(
+///     disable_line_comment("MD012"),
+///     "<!-- md-check-disable-line MD012 -->"
+/// );
+/// ```
+pub fn disable_line_comment(rule_id: &str) -> String {
+    format!("<!-- md-check-disable-line {} -->", rule_id)
+}
+
+/// Whether `html` is nothing but an md-check or markdownlint suppression
+/// comment.
+pub fn is_directive(html: &str) -> bool {
+    DIRECTIVE
+        .find(html.trim())
+        .is_some_and(|m| m.len() == html.trim().len())
+}
+
+/// Which rules are suppressed on which lines by comments in a document.
+///
+/// Supported comments, each naming zero or more rule IDs or aliases,
+/// matched case-insensitively (none means all rules):
+///
+/// - `<!-- md-check-disable-next-line MD012 -->` suppresses the next line.
+/// - `<!-- md-check-disable-line MD012 -->` suppresses its own line.
+/// - `<!-- md-check-disable MD012 -->` suppresses the following lines up to a
+///   matching `<!-- md-check-enable MD012 -->` or the end of the file.
+/// - `<!-- md-check-disable-file MD012 -->` suppresses the whole file, unless
+///   undone by `<!-- md-check-enable-file MD012 -->`.
+///
+/// Each comment may also be spelled `markdownlint-…`, as in files written
+/// for markdownlint.
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    /// Rules suppressed throughout the file.
+    file: Scope,
+    /// Suppressed rules of each affected line, in line order.
+    lines: Vec<(usize, Scope)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum Scope {
+    #[default]
+    None,
+    All,
+    Rules(HashSet<String>),
+}
+
+impl Scope {
+    fn contains(&self, rule_id: &str) -> bool {
+        match self {
+            Scope::None => false,
+            Scope::All => true,
+            Scope::Rules(rules) => rules.contains(&rule_id.to_ascii_lowercase()),
+        }
+    }
+
+    fn add(&mut self, rules: &[String]) {
+        if rules.is_empty() {
+            *self = Scope::All;
+            return;
+        }
+        match self {
+            Scope::All => {}
+            Scope::None => *self = Scope::Rules(rules.iter().cloned().collect()),
+            Scope::Rules(set) => set.extend(rules.iter().cloned()),
+        }
+    }
+
+    fn merge(&mut self, other: Scope) {
+        match other {
+            Scope::None => {}
+            Scope::All => *self = Scope::All,
+            Scope::Rules(rules) => self.add(&rules.into_iter().collect::<Vec<_>>()),
+        }
+    }
+
+    fn remove(&mut self, rules: &[String]) {
+        match self {
+            _ if rules.is_empty() => *self = Scope::None,
+            Scope::Rules(set) => {
+                for rule in rules {
+                    set.remove(rule);
+                }
+                if set.is_empty() {
+                    *self = Scope::None;
+                }
+            }
+            // Re-enabling single rules inside a disable-all block is not
+            // tracked; the block stays in effect
+            Scope::All | Scope::None => {}
+        }
+    }
+}
+
+impl Suppressions {
+    /// Collects the suppression comments of `content`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::suppression::Suppressions;
+    ///
+    /// let content = "<!-- md-check-disable-next-line MD012 -->\n\n\nText\n";
+    /// let suppressions = Suppressions::parse(content);
+    ///
+    /// assert!(suppressions.is_suppressed("MD012", 2));
+    /// assert!(!suppressions.is_suppressed("NO_HTML", 2));
+    /// assert!(!suppressions.is_suppressed("MD012", 3));
+    /// ```
+    pub fn parse(content: &str) -> Self {
+        let directives = directives(content);
+        let mut directives = directives.iter().peekable();
+        let mut file = Scope::None;
+        let mut lines = Vec::new();
+        let mut block = Scope::None;
+        let mut next_line = Scope::None;
+
+        for idx in 0..lines_with_endings(content).count() {
+            let mut scope = block.clone();
+            scope.merge(std::mem::take(&mut next_line));
+
+            while let Some((_, kind, rules)) = directives.next_if(|(line, ..)| *line == idx + 1) {
+                match *kind {
+                    "disable-next-line" => next_line.add(rules),
+                    "disable-line" => scope.add(rules),
+                    "disable-file" => file.add(rules),
+                    "enable-file" => file.remove(rules),
+                    "disable" => block.add(rules),
+                    _ => block.remove(rules),
+                }
+            }
+
+            if scope != Scope::None {
+                lines.push((idx + 1, scope));
+            }
+        }
+        Self { file, lines }
+    }
+
+    /// Whether `rule_id` is suppressed on the 1-based `line`.
+    pub fn is_suppressed(&self, rule_id: &str, line: usize) -> bool {
+        self.file.contains(rule_id)
+            || self
+                .lines
+                .binary_search_by_key(&line, |(l, _)| *l)
+                .is_ok_and(|idx| self.lines[idx].1.contains(rule_id))
+    }
+}
+
+/// The suppression comments of `content` in document order, with their
+/// 1-based line, kind and lowercase rule names. Comments shown in code
+/// blocks and code spans are examples, not directives, so only HTML the
+/// parser reports counts.
+fn directives(content: &str) -> Vec<(usize, &str, Vec<String>)> {
+    let line_index = LineIndex::new(content);
+    let mut directives = Vec::new();
+    for (event, range) in Parser::new_ext(content, PARSER_OPTIONS).into_offset_iter() {
+        if !matches!(event, Event::Html(_) | Event::InlineHtml(_)) {
+            continue;
+        }
+        for captures in DIRECTIVE.captures_iter(&content[range.clone()]) {
+            let rules = captures[2]
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|rule| !rule.is_empty())
+                .map(str::to_ascii_lowercase)
+                .collect();
+            let start = range.start + captures.get_match().start();
+            let kind = captures.get(1).map_or("", |m| m.as_str());
+            directives.push((line_index.line(start), kind, rules));
+        }
+    }
+    directives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disable_and_enable_blocks() {
+        let content = "\
+<!-- md-check-disable MD012, LINE_TOO_LONG -->
+one
+<!-- md-check-enable LINE_TOO_LONG -->
+two
+<!-- md-check-enable -->
+three
+<!-- md-check-disable-next-line -->
+four
+five
+";
+        let suppressions = Suppressions::parse(content);
+
+        assert!(suppressions.is_suppressed("MD012", 2));
+        assert!(suppressions.is_suppressed("LINE_TOO_LONG", 2));
+        assert!(suppressions.is_suppressed("MD012", 4));
+        assert!(!suppressions.is_suppressed("LINE_TOO_LONG", 4));
+        assert!(!suppressions.is_suppressed("MD012", 6));
+        assert!(suppressions.is_suppressed("NO_HTML", 8));
+        assert!(!suppressions.is_suppressed("NO_HTML", 9));
+    }
+
+    #[test]
+    fn test_markdownlint_comments() {
+        let content = "\
+<!-- markdownlint-disable-file no-inline-html -->
+<!-- markdownlint-disable md012 -->
+one
+<!-- markdownlint-enable MD012 -->
+two <!-- markdownlint-disable-line line-length -->
+three
+";
+        let suppressions = Suppressions::parse(content);
+
+        assert!(suppressions.is_suppressed("NO-INLINE-HTML", 6));
+        assert!(suppressions.is_suppressed("MD012", 3));
+        assert!(!suppressions.is_suppressed("MD012", 5));
+        assert!(suppressions.is_suppressed("line-length", 5));
+        assert!(!suppressions.is_suppressed("line-length", 6));
+    }
+
+    #[test]
+    fn test_comments_in_code_are_examples() {
+        let content = "\
+Disable a rule like this:
+
+```markdown
+<!-- md-check-disable MD012 -->
+```
+
+    <!-- md-check-disable-file -->
+
+Or inline, `<!-- md-check-disable-line -->`.
+";
+        let suppressions = Suppressions::parse(content);
+
+        assert!((1..=10).all(|line| !suppressions.is_suppressed("MD012", line)));
+    }
+
+    #[test]
+    fn test_is_directive() {
+        assert!(is_directive("<!-- md-check-disable-next-line MD012 -->\n"));
+        assert!(is_directive("<!-- markdownlint-disable-file MD033 -->"));
+        assert!(is_directive("<!--md-check-enable-->"));
+        assert!(!is_directive("<!-- a regular comment -->"));
+        assert!(!is_directive("<div><!-- md-check-disable --></div>"));
+    }
+}
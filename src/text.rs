@@ -0,0 +1,337 @@
+use thiserror::Error;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error(
+        "file is not valid UTF-8 (invalid byte 0x{byte:02X} at line {line}, column {column}); \
+         convert it to UTF-8"
+    )]
+    InvalidUtf8 {
+        byte: u8,
+        line: usize,
+        column: usize,
+    },
+    #[error("file looks like UTF-16 but contains invalid UTF-16 data; convert it to UTF-8")]
+    InvalidUtf16,
+}
+
+impl DecodeError {
+    /// Line and column of the first invalid byte, if known.
+    pub fn position(&self) -> (usize, Option<usize>) {
+        match self {
+            DecodeError::InvalidUtf8 { line, column, .. } => (*line, Some(*column)),
+            DecodeError::InvalidUtf16 => (1, None),
+        }
+    }
+}
+
+/// A line terminator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl LineEnding {
+    /// Parses a terminator as returned by [`lines_with_endings`]; `None` for
+    /// the empty terminator of a final line.
+    pub fn from_terminator(terminator: &str) -> Option<Self> {
+        match terminator {
+            "\n" => Some(LineEnding::Lf),
+            "\r\n" => Some(LineEnding::Crlf),
+            "\r" => Some(LineEnding::Cr),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+}
+
+/// Encoding a document was stored in, so fixes can be written back unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    /// UTF-16, recognized by its byte order mark or guessed without one.
+    Utf16Le {
+        bom: bool,
+    },
+    Utf16Be {
+        bom: bool,
+    },
+}
+
+/// Decodes a Markdown document.
+///
+/// UTF-8 is expected. A leading byte order mark is stripped, and UTF-16 is
+/// recognized by its byte order mark or, without one, by the zero bytes of
+/// ASCII text.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::text::{decode, Encoding};
+///
+/// let (text, encoding) = decode(b"\xEF\xBB\xBF# Title\n").unwrap();
+/// assert_eq!(text, "# Title\n");
+/// assert_eq!(encoding, Encoding::Utf8Bom);
+///
+/// let error = decode(b"# Caf\xE9\n").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "file is not valid UTF-8 (invalid byte 0xE9 at line 1, column 6); convert it to UTF-8"
+/// );
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<(String, Encoding), DecodeError> {
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return decode_utf8(rest).map(|text| (text, Encoding::Utf8Bom));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        return decode_utf16(rest, u16::from_le_bytes)
+            .map(|text| (text, Encoding::Utf16Le { bom: true }));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        return decode_utf16(rest, u16::from_be_bytes)
+            .map(|text| (text, Encoding::Utf16Be { bom: true }));
+    }
+
+    match detect_utf16(bytes) {
+        Some(encoding @ Encoding::Utf16Le { .. }) => {
+            decode_utf16(bytes, u16::from_le_bytes).map(|text| (text, encoding))
+        }
+        Some(encoding @ Encoding::Utf16Be { .. }) => {
+            decode_utf16(bytes, u16::from_be_bytes).map(|text| (text, encoding))
+        }
+        _ => decode_utf8(bytes).map(|text| (text, Encoding::Utf8)),
+    }
+}
+
+/// Encodes `text` back into `encoding`, restoring its byte order mark if
+/// it had one.
+pub fn encode(text: &str, encoding: Encoding) -> Vec<u8> {
+    match encoding {
+        Encoding::Utf8 => text.as_bytes().to_vec(),
+        Encoding::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+        Encoding::Utf16Le { bom } => {
+            encode_utf16(text, bom.then_some(UTF16_LE_BOM), u16::to_le_bytes)
+        }
+        Encoding::Utf16Be { bom } => {
+            encode_utf16(text, bom.then_some(UTF16_BE_BOM), u16::to_be_bytes)
+        }
+    }
+}
+
+fn encode_utf16(text: &str, bom: Option<&[u8]>, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    bom.unwrap_or_default()
+        .iter()
+        .copied()
+        .chain(text.encode_utf16().flat_map(to_bytes))
+        .collect()
+}
+
+fn decode_utf8(bytes: &[u8]) -> Result<String, DecodeError> {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok(text.to_string()),
+        Err(e) => {
+            // Everything before the error is valid, so it can be indexed
+            let valid = std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default();
+            let (line, column) = LineIndex::new(valid).line_col(valid, valid.len());
+            Err(DecodeError::InvalidUtf8 {
+                byte: bytes[e.valid_up_to()],
+                line,
+                column,
+            })
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> Result<String, DecodeError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidUtf16);
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|_| DecodeError::InvalidUtf16)
+}
+
+/// Guesses UTF-16 without a byte order mark from the zero bytes that ASCII
+/// characters leave in every other position.
+fn detect_utf16(bytes: &[u8]) -> Option<Encoding> {
+    let sample = &bytes[..bytes.len().min(64)];
+    if sample.len() < 4 || !sample.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let zero_at = |parity: usize| {
+        sample
+            .iter()
+            .enumerate()
+            .all(|(i, &b)| (i % 2 == parity) == (b == 0))
+    };
+    if zero_at(1) {
+        Some(Encoding::Utf16Le { bom: false })
+    } else if zero_at(0) {
+        Some(Encoding::Utf16Be { bom: false })
+    } else {
+        None
+    }
+}
+
+/// Splits `content` into lines with their terminators, which may be `\n`,
+/// `\r\n`, `\r`, or empty for a final line without one.
+///
+/// # Examples
+///
+/// ```
+/// use md_check::text::lines_with_endings;
+///
+/// let lines: Vec<_> = lines_with_endings("a\r\nb\rc\nd").collect();
+///
+/// assert_eq!(lines, vec![("a", "\r\n"), ("b", "\r"), ("c", "\n"), ("d", "")]);
+/// ```
+pub fn lines_with_endings(content: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = content;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let (line, ending, next) = match rest.find(['\n', '\r']) {
+            Some(idx) if rest[idx..].starts_with("\r\n") => {
+                (&rest[..idx], &rest[idx..idx + 2], &rest[idx + 2..])
+            }
+            Some(idx) => (&rest[..idx], &rest[idx..idx + 1], &rest[idx + 1..]),
+            None => (rest, "", ""),
+        };
+        rest = next;
+        Some((line, ending))
+    })
+}
+
+/// Maps byte offsets to 1-based line and column numbers, treating `\n`,
+/// `\r\n` and `\r` alike as line breaks.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(content: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut offset = 0;
+        for (line, ending) in lines_with_endings(content) {
+            offset += line.len() + ending.len();
+            if !ending.is_empty() {
+                line_starts.push(offset);
+            }
+        }
+        Self { line_starts }
+    }
+
+    /// Line containing `offset`.
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+
+    /// Line and column of `offset` in `content`, which must be the text the
+    /// index was built from. Columns count characters, not bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use md_check::text::LineIndex;
+    ///
+    /// let content = "# Título\r\n\r\n[link](x.md)";
+    /// let index = LineIndex::new(content);
+    ///
+    /// assert_eq!(index.line_col(content, content.find("x.md").unwrap()), (3, 8));
+    /// ```
+    pub fn line_col(&self, content: &str, offset: usize) -> (usize, usize) {
+        let line = self.line(offset);
+        let start = self.line_starts[line - 1];
+        let column = content
+            .get(start..offset)
+            .map_or(1, |prefix| prefix.chars().count() + 1);
+        (line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf16() {
+        let text = "# Überschrift\r\n";
+        for (with_bom, without_bom) in [
+            (
+                Encoding::Utf16Le { bom: true },
+                Encoding::Utf16Le { bom: false },
+            ),
+            (
+                Encoding::Utf16Be { bom: true },
+                Encoding::Utf16Be { bom: false },
+            ),
+        ] {
+            let bytes = encode(text, with_bom);
+            assert_eq!(decode(&bytes), Ok((text.to_string(), with_bom)));
+
+            // Without the byte order mark, which encoding leaves out again
+            assert_eq!(decode(&bytes[2..]), Ok((text.to_string(), without_bom)));
+            assert_eq!(encode(text, without_bom), &bytes[2..]);
+        }
+
+        assert_eq!(decode(b"\xFF\xFE#\x00 "), Err(DecodeError::InvalidUtf16));
+    }
+
+    #[test]
+    fn test_decode_reports_position_of_invalid_byte() {
+        assert_eq!(
+            decode(b"# Title\r\n\r\nna\xEFve\n"),
+            Err(DecodeError::InvalidUtf8 {
+                byte: 0xEF,
+                line: 3,
+                column: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_line_index_handles_all_line_endings() {
+        let content = "one\ntwo\r\nthree\rfour";
+        let index = LineIndex::new(content);
+
+        assert_eq!(index.line_col(content, 0), (1, 1));
+        assert_eq!(
+            index.line_col(content, content.find("two").unwrap()),
+            (2, 1)
+        );
+        assert_eq!(
+            index.line_col(content, content.find("three").unwrap()),
+            (3, 1)
+        );
+        assert_eq!(index.line_col(content, content.find("ur").unwrap()), (4, 3));
+        // The terminator belongs to the line it ends
+        assert_eq!(index.line(content.find('\r').unwrap() + 1), 2);
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+use colored::*;
+use notify_debouncer_mini::notify::RecursiveMode;
+use notify_debouncer_mini::{DebounceEventResult, new_debouncer};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use md_check::discovery::FileDiscovery;
+use md_check::link_checker::{self, CheckResult, LinkChecker, LinkInfo};
+use md_check::rules::common::LintError;
+
+use crate::{Analyzer, Source, format_lint_error};
+
+/// How long the file system has to be quiet before a re-check starts.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// ANSI sequence that clears the terminal and moves the cursor home.
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+/// The last analysis of one document.
+struct Document {
+    content: String,
+    lint_errors: Vec<LintError>,
+}
+
+/// Checks `paths`, then re-checks them whenever files change until the
+/// process is interrupted.
+///
+/// Only files that changed are read and linted again. Local links of every
+/// document are re-validated on each run, since they depend on other files,
+/// but remote URLs are requested only the first time they appear.
+pub async fn run(
+    paths: &[PathBuf],
+    discovery: &FileDiscovery,
+    analyzer: &Analyzer,
+    checker: Option<&LinkChecker>,
+) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+        let _ = tx.send(result);
+    })?;
+    for path in paths {
+        debouncer.watcher().watch(path, RecursiveMode::Recursive)?;
+    }
+
+    let mut documents: BTreeMap<PathBuf, Document> = BTreeMap::new();
+    let mut remote_results: HashMap<String, CheckResult> = HashMap::new();
+    // `None` until the first run, which checks everything
+    let mut changed: Option<HashSet<PathBuf>> = None;
+
+    loop {
+        // Discover again so that added, removed and newly ignored files count
+        let mut files = Vec::new();
+        discovery.discover(paths, |path| files.push(path));
+        let removed = documents.len();
+        documents.retain(|path, _| files.contains(path));
+        let removed = removed - documents.len();
+
+        let mut rechecked = 0;
+        for file in files {
+            let stale = match &changed {
+                None => true,
+                Some(changed) => {
+                    !documents.contains_key(&file) || changed.contains(&canonical(&file))
+                }
+            };
+            if !stale {
+                continue;
+            }
+
+            let mut lint_errors = Vec::new();
+            let content = match analyzer.read(Source::File(file.clone()), &mut lint_errors) {
+                // Editors often touch a file several times per save
+                Some((_, content))
+                    if documents
+                        .get(&file)
+                        .is_some_and(|document| document.content == content) =>
+                {
+                    continue;
+                }
+                Some((file_path, content)) => {
+                    lint_errors.extend(analyzer.lint(&file_path, &content));
+                    content
+                }
+                None => String::new(),
+            };
+            rechecked += 1;
+            documents.insert(
+                file,
+                Document {
+                    content,
+                    lint_errors,
+                },
+            );
+        }
+
+        // Changes to other files, such as the cache directory, need no redraw
+        if changed.is_none() || rechecked > 0 || removed > 0 {
+            let results = match checker {
+                Some(checker) => {
+                    check_links(&documents, checker, analyzer, &mut remote_results).await
+                }
+                None => Vec::new(),
+            };
+            render(&documents, &results, rechecked)?;
+        }
+
+        let Some(batch) = rx.recv().await else {
+            return Ok(());
+        };
+        let mut paths_changed = HashSet::new();
+        for result in std::iter::once(batch).chain(std::iter::from_fn(|| rx.try_recv().ok())) {
+            match result {
+                Ok(events) => {
+                    paths_changed.extend(events.into_iter().map(|event| canonical(&event.path)))
+                }
+                Err(e) => eprintln!("{}: {}", "Watch error".red(), e),
+            }
+        }
+        changed = Some(paths_changed);
+    }
+}
+
+/// Validates the links of every document, requesting only remote URLs that
+/// have no result yet.
+async fn check_links(
+    documents: &BTreeMap<PathBuf, Document>,
+    checker: &LinkChecker,
+    analyzer: &Analyzer,
+    remote_results: &mut HashMap<String, CheckResult>,
+) -> Vec<CheckResult> {
+    let links: Vec<LinkInfo> = documents
+        .iter()
+        .flat_map(|(path, document)| {
+            link_checker::extract_links(&document.content, path, &analyzer.options.local_options)
+        })
+        .collect();
+
+    let mut queued = HashSet::new();
+    let new_links: Vec<LinkInfo> = links
+        .iter()
+        .filter(|link| !remote_results.contains_key(&link.url) && queued.insert(&link.url))
+        .cloned()
+        .collect();
+    for result in checker.check(new_links).await {
+        remote_results.insert(result.link.url.clone(), result);
+    }
+
+    let mut results: Vec<CheckResult> = links
+        .into_iter()
+        .filter_map(|link| {
+            let result = remote_results.get(&link.url)?;
+            Some(CheckResult {
+                link,
+                ..result.clone()
+            })
+        })
+        .collect();
+    for (path, document) in documents {
+        results.extend(link_checker::check_local_links(
+            &document.content,
+            path,
+            &analyzer.options.local_options,
+        ));
+    }
+    results.sort_by(|a, b| {
+        (&a.link.file_path, a.link.line, &a.link.url).cmp(&(
+            &b.link.file_path,
+            b.link.line,
+            &b.link.url,
+        ))
+    });
+    results
+}
+
+/// Clears the terminal and prints the problems of the current state.
+fn render(
+    documents: &BTreeMap<PathBuf, Document>,
+    results: &[CheckResult],
+    rechecked: usize,
+) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    write!(out, "{}", CLEAR_SCREEN)?;
+    writeln!(
+        out,
+        "{} {} Markdown files ({} re-checked)",
+        "Watching".cyan(),
+        documents.len(),
+        rechecked
+    )?;
+
+    let link_errors = link_checker::format_check_results(results);
+    if !link_errors.is_empty() {
+        writeln!(out, "\n{}", "Problematic links:".red())?;
+        for error in &link_errors {
+            writeln!(out, "{}", error)?;
+        }
+    }
+
+    let lint_errors: Vec<&LintError> = documents
+        .values()
+        .flat_map(|document| &document.lint_errors)
+        .collect();
+    if !lint_errors.is_empty() {
+        writeln!(out, "\n{}", "Style errors:".red())?;
+        for error in &lint_errors {
+            writeln!(out, "{}", format_lint_error(error))?;
+        }
+    }
+
+    if link_errors.is_empty() && lint_errors.is_empty() {
+        writeln!(out, "\n{}", "No problems found.".green())?;
+    }
+    writeln!(
+        out,
+        "\n{}",
+        "Waiting for changes... (Ctrl+C to quit)".dimmed()
+    )?;
+    out.flush()
+}
+
+/// Resolves symlinks and relative components, so that paths reported by the
+/// file watcher compare equal to discovered ones.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
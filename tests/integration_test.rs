@@ -4,6 +4,9 @@ use std::error::Error;
 use std::fs;
 use tempfile::TempDir;
 
+// Mock servers listen on 127.0.0.1, which URL_HYGIENE reports by default
+const ALLOW_LOCAL_URLS: &str = "[rules.url_hygiene]\nlocal_addresses = false\n";
+
 #[test]
 fn test_cli_no_files() -> Result<(), Box<dyn Error>> {
     let temp_dir = TempDir::new()?;
@@ -51,9 +54,17 @@ fn test_skip_links_flag() -> Result<(), Box<dyn Error>> {
         // Should not see any link checking output
         .stdout(
             predicate::str::contains("Found 1 Markdown files")
-                .and(predicate::str::contains("Analyzing")),
+                .and(predicate::str::contains("Analyzing").not()),
         );
 
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(&file_path)
+        .arg("--skip-links")
+        .arg("--verbose")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Analyzing"));
+
     Ok(())
 }
 
@@ -67,3 +78,1103 @@ fn test_invalid_file_path() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_config_file_user_agent() -> Result<(), Box<dyn Error>> {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/page")
+        .match_header("user-agent", "config-agent")
+        .with_status(200)
+        .create();
+
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        format!(
+            "[links]\nuser_agent = \"config-agent\"\n{}",
+            ALLOW_LOCAL_URLS
+        ),
+    )?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        format!("[Page]({}/page)\n", server.url()),
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GOOD"));
+
+    mock.assert();
+    Ok(())
+}
+
+#[test]
+fn test_cli_user_agent_overrides_config() -> Result<(), Box<dyn Error>> {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/page")
+        .match_header("user-agent", "cli-agent")
+        .with_status(200)
+        .create();
+
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        format!(
+            "[links]\nuser_agent = \"config-agent\"\n{}",
+            ALLOW_LOCAL_URLS
+        ),
+    )?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        format!("[Page]({}/page)\n", server.url()),
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .arg("--user-agent")
+        .arg("cli-agent")
+        .assert()
+        .success();
+
+    mock.assert();
+    Ok(())
+}
+
+#[test]
+fn test_invalid_config_file() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[links]\nunknown_key = 1\n",
+    )?;
+    fs::write(temp_dir.path().join("test.md"), "# Title\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("failed to parse config file"));
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_reports_unchecked_links() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("other.md"), "# Other\n")?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        "[Remote](https://example.com)\n\n[Local](other.md#other) [Missing](missing.md)\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .arg("--offline")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("UNCHECKED - https://example.com")
+                .and(predicate::str::contains("Unchecked: 1"))
+                .and(predicate::str::contains("File not found"))
+                .and(predicate::str::contains("other.md#other").not()),
+        );
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_serves_cached_results() -> Result<(), Box<dyn Error>> {
+    let mut server = mockito::Server::new();
+    let mock = server.mock("GET", "/page").with_status(200).create();
+
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join(".md-check.toml"), ALLOW_LOCAL_URLS)?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        format!("[Page]({}/page)\n", server.url()),
+    )?;
+
+    // An online run with --cache records the result
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .arg("--cache")
+        .assert()
+        .success();
+    mock.assert();
+    assert!(temp_dir.path().join(".md-check-cache/links.json").is_file());
+
+    // The offline run reuses it without another request
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .arg("--offline")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("GOOD (cached)"));
+    mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn test_scheme_validation() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.md");
+    fs::write(
+        &file_path,
+        "[Typo](htps://example.com) [Mail](mailto:support@example)\n\
+         [Phone](tel:+1-201-555-0123) [Chat](slack://open) <help@example.com>\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(&file_path)
+        .arg("--offline")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("did you mean 'https'?")
+                .and(predicate::str::contains("domain has no top-level domain"))
+                .and(predicate::str::contains(
+                    "URL scheme 'slack' is not allowed",
+                ))
+                .and(predicate::str::contains("tel:+1-201-555-0123").not())
+                .and(predicate::str::contains("help@example.com").not()),
+        );
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(&file_path)
+        .arg("--offline")
+        .arg("--allow-scheme")
+        .arg("slack")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("slack").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_url_hygiene_rule() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.md");
+    fs::write(
+        &file_path,
+        "[Trailing](<https://example.com/page.>)\n\n\
+         [Local](http://192.168.1.10/admin)\n\n\
+         [Tracked](https://example.com/?utm_source=newsletter)\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(&file_path)
+        .arg("--skip-links")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("URL ends with '.'")
+                .and(predicate::str::contains("local or private address"))
+                .and(predicate::str::contains("tracking parameter 'utm_source'")),
+        );
+
+    // Categories can be switched off individually
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[rules.url_hygiene]\ntrailing_punctuation = false\nlocal_addresses = false\ntracking_params = false\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("test.md")
+        .arg("--skip-links")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_link_results_are_sorted_by_file_and_line() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("b.md"),
+        "[One](missing-1.md)\n\n[Two](missing-2.md)\n",
+    )?;
+    fs::write(temp_dir.path().join("a.md"), "[Three](missing-3.md)\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    let output = cmd.arg(temp_dir.path()).arg("--offline").output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    let report = stdout
+        .split("Problematic links:")
+        .nth(1)
+        .ok_or("missing link report")?;
+    let positions: Vec<usize> = ["missing-3.md", "missing-1.md", "missing-2.md"]
+        .iter()
+        .map(|url| report.find(url).ok_or("missing link"))
+        .collect::<Result<_, _>>()?;
+
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    Ok(())
+}
+
+#[test]
+fn test_parallel_lint_output_is_deterministic() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    for i in 0..12 {
+        fs::write(
+            temp_dir.path().join(format!("doc-{:02}.md", i)),
+            "# Title\n\n\n\nText\n\n\n\nMore text\n",
+        )?;
+    }
+
+    let style_report = |jobs: &str| -> Result<String, Box<dyn Error>> {
+        let mut cmd = cargo_bin_cmd!("md-check");
+        let output = cmd
+            .arg(temp_dir.path())
+            .arg("--skip-links")
+            .args(["--jobs", jobs])
+            .output()?;
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    let sequential = style_report("1")?;
+    assert!(sequential.find("doc-00.md") < sequential.find("doc-11.md"));
+    assert_eq!(style_report("4")?, sequential);
+    assert_eq!(style_report("8")?, sequential);
+    Ok(())
+}
+
+#[test]
+fn test_lint_cache_skips_unchanged_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("test.md"), "# Title\n\n\n\nText\n")?;
+
+    let run = || {
+        let mut cmd = cargo_bin_cmd!("md-check");
+        cmd.current_dir(temp_dir.path())
+            .args(["test.md", "--skip-links", "--cache", "--verbose"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("MD012"))
+    };
+
+    run().stdout(predicate::str::contains("lint cache hit").not());
+    assert!(temp_dir.path().join(".md-check-cache/lint.json").is_file());
+    run().stdout(predicate::str::contains("lint cache hit"));
+
+    // Changing the rule configuration invalidates every entry
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[rules.url_hygiene]\ntracking_params = false\n",
+    )?;
+    run()
+        .stdout(predicate::str::contains("Lint cache discarded"))
+        .stdout(predicate::str::contains("lint cache hit").not());
+
+    // Editing the file invalidates its entry
+    run().stdout(predicate::str::contains("lint cache hit"));
+    fs::write(temp_dir.path().join("test.md"), "# Title\n\n\n\nNew text\n")?;
+    run().stdout(predicate::str::contains("lint cache hit").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_file_discovery_filters() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir_all(temp_dir.path().join("node_modules/pkg"))?;
+    fs::create_dir_all(temp_dir.path().join(".hidden"))?;
+    fs::write(temp_dir.path().join(".gitignore"), "node_modules/\n")?;
+    fs::write(temp_dir.path().join("README.md"), "# Readme\n")?;
+    fs::write(temp_dir.path().join("CHANGELOG.md"), "# Changelog\n")?;
+    fs::write(temp_dir.path().join("node_modules/pkg/doc.md"), "# Doc\n")?;
+    fs::write(temp_dir.path().join(".hidden/notes.md"), "# Notes\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path())
+        .args(["--skip-links", "--exclude", "CHANGELOG.md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 Markdown files."));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path())
+        .args(["--skip-links", "--no-ignore", "--hidden", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 4 Markdown files."))
+        .stdout(predicate::str::contains("doc.md"))
+        .stdout(predicate::str::contains("notes.md"));
+
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[files]\ninclude = [\"README.md\"]\n",
+    )?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--skip-links", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 Markdown files."))
+        .stdout(predicate::str::contains("README.md"));
+
+    Ok(())
+}
+
+#[test]
+fn test_explicit_files_are_checked_regardless_of_extension() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("notes.txt"), "# Notes\n\n\n\nText\n")?;
+    fs::write(temp_dir.path().join("Guide.MARKDOWN"), "# Guide\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path().join("notes.txt"))
+        .arg("--skip-links")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("MD012"));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path())
+        .args(["--skip-links", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Guide.MARKDOWN"))
+        .stdout(predicate::str::contains("notes.txt").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_stdin_uses_stdin_filename() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir_all(temp_dir.path().join("docs"))?;
+    fs::write(temp_dir.path().join("docs/guide.md"), "# Guide\n")?;
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[links]\nallowed_schemes = [\"https\"]\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["-", "--offline", "--stdin-filename", "docs/new.md"])
+        .write_stdin(
+            "# New\n\n[Guide](guide.md)\n[Missing](missing.md)\n[Mail](mailto:a@example.com)\n",
+        )
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Found 1 Markdown files."))
+        .stdout(predicate::str::contains("File not found"))
+        .stdout(predicate::str::contains("[in file docs/new.md:4]"))
+        .stdout(predicate::str::contains("guide.md").not())
+        .stdout(predicate::str::contains(
+            "URL scheme 'mailto' is not allowed",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["-", "--skip-links"])
+        .write_stdin("# Title\n\n\n\nText\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("<stdin>:3"));
+
+    Ok(())
+}
+
+#[test]
+fn test_fix_stdin_writes_document_to_stdout() -> Result<(), Box<dyn Error>> {
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["-", "--fix", "--stdin-filename", "doc.md"])
+        .write_stdin("# Title\n\n\n\nText\n")
+        .assert()
+        .success()
+        .stdout("# Title\n\nText\n");
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["-", "--fix"])
+        .write_stdin("# Title\n\n\n\n[Empty]()\n")
+        .assert()
+        .failure()
+        .stdout("# Title\n\n[Empty]()\n")
+        .stderr(predicate::str::contains("NO_EMPTY_LINKS"));
+
+    Ok(())
+}
+
+#[test]
+fn test_fix_rewrites_files() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.md");
+    fs::write(&file_path, "# Title\n\n\n\nText\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(&file_path)
+        .args(["--skip-links", "--fix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed"));
+
+    assert_eq!(fs::read_to_string(&file_path)?, "# Title\n\nText\n");
+    Ok(())
+}
+
+#[test]
+fn test_fix_leaves_suppressed_lines_alone() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let file_path = temp_dir.path().join("test.md");
+    let content = "# Title\n\n<!-- markdownlint-disable MD012 -->\n\n\n\nText\n";
+    fs::write(&file_path, content)?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg("fix")
+        .arg(&file_path)
+        .arg("--skip-links")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fixed").not());
+
+    assert_eq!(fs::read_to_string(&file_path)?, content);
+    Ok(())
+}
+
+#[test]
+fn test_encodings_and_line_endings() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("bom.md"),
+        b"\xEF\xBB\xBF# Title\r\n\r\n[Empty]()\r\n",
+    )?;
+    let utf16: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain(
+            "# Title\n\nText\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        )
+        .collect();
+    fs::write(temp_dir.path().join("utf16.md"), utf16)?;
+    fs::write(temp_dir.path().join("latin1.md"), b"# Title\n\nCaf\xE9\n")?;
+    fs::write(temp_dir.path().join("mixed.md"), "# Title\r\n\nText\r\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path())
+        .args(["--skip-links", "--verbose"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("bom.md:3:1 Empty link URL found"))
+        .stdout(predicate::str::contains("utf16.md").count(1))
+        .stdout(predicate::str::contains(
+            "[ENCODING] ".to_string()
+                + &temp_dir.path().join("latin1.md").display().to_string()
+                + ":3:4 file is not valid UTF-8",
+        ))
+        .stdout(predicate::str::contains(
+            "mixed.md:2:1 Expected CRLF line ending, found LF",
+        ));
+
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[rules.line_endings]\nstyle = \"lf\"\n",
+    )?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["mixed.md", "bom.md", "--skip-links", "--fix"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("LINE_ENDINGS").not());
+
+    assert_eq!(
+        fs::read(temp_dir.path().join("mixed.md"))?,
+        b"# Title\n\nText\n"
+    );
+    assert_eq!(
+        fs::read(temp_dir.path().join("bom.md"))?,
+        b"\xEF\xBB\xBF# Title\n\n[Empty]()\n"
+    );
+
+    // UTF-16 without a byte order mark does not gain one
+    let utf16_le =
+        |text: &str| -> Vec<u8> { text.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+    fs::write(
+        temp_dir.path().join("clean16.md"),
+        utf16_le("# Title\n\nText\n"),
+    )?;
+    fs::write(
+        temp_dir.path().join("fixed16.md"),
+        utf16_le("# Title\n\n\n\nText\n"),
+    )?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["clean16.md", "fixed16.md", "--skip-links", "--fix"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read(temp_dir.path().join("clean16.md"))?,
+        utf16_le("# Title\n\nText\n")
+    );
+    assert_eq!(
+        fs::read(temp_dir.path().join("fixed16.md"))?,
+        utf16_le("# Title\n\nText\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_watch_rechecks_changed_files() -> Result<(), Box<dyn Error>> {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("test.md"), "# Title\n\nText\n")?;
+
+    // Stops the watcher even when an assertion fails
+    struct KillOnDrop(std::process::Child);
+    impl Drop for KillOnDrop {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    let mut child = KillOnDrop(
+        Command::new(env!("CARGO_BIN_EXE_md-check"))
+            .current_dir(temp_dir.path())
+            .args([".", "--skip-links", "--watch"])
+            .stdout(Stdio::piped())
+            .spawn()?,
+    );
+    let stdout = child.0.stdout.take().ok_or("no stdout")?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let _ = tx.send(line);
+        }
+    });
+
+    // Collects output up to the end of the next report
+    let next_report = || -> Result<String, Box<dyn Error>> {
+        let deadline = Instant::now() + Duration::from_secs(20);
+        let mut report = String::new();
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            let line = rx.recv_timeout(timeout)?;
+            report.push_str(&line);
+            report.push('\n');
+            if line.contains("Waiting for changes") {
+                return Ok(report);
+            }
+        }
+        Err("timed out waiting for a report".into())
+    };
+
+    let report = next_report()?;
+    assert!(report.contains("Watching 1 Markdown files (1 re-checked)"));
+    assert!(report.contains("No problems found."));
+
+    fs::write(temp_dir.path().join("test.md"), "# Title\n\n\n\nText\n")?;
+    let report = next_report()?;
+    assert!(report.contains("MD012"));
+
+    fs::write(temp_dir.path().join("other.md"), "# Other\n")?;
+    let report = next_report()?;
+    assert!(report.contains("Watching 2 Markdown files (1 re-checked)"));
+    assert!(report.contains("MD012"));
+
+    drop(child);
+    Ok(())
+}
+
+#[test]
+fn test_suppression_comments() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        "# Title\n\n<!-- md-check-disable-next-line NO_HTML -->\n<div>ok</div>\n\n<div>flagged</div>\n\
+         <!-- md-check-disable MD012 -->\n\n\n\nText\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg(temp_dir.path())
+        .arg("--skip-links")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(":6:1 Raw HTML found in markdown"))
+        .stdout(predicate::str::contains(":4:").not())
+        .stdout(predicate::str::contains("MD012").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_lsp_publishes_diagnostics_and_code_actions() -> Result<(), Box<dyn Error>> {
+    use serde_json::{Value, json};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("test.md");
+    let uri = format!("file://{}", path.display());
+
+    struct KillOnDrop(std::process::Child);
+    impl Drop for KillOnDrop {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    let mut child = KillOnDrop(
+        Command::new(env!("CARGO_BIN_EXE_md-check"))
+            .current_dir(temp_dir.path())
+            .args(["lsp", "--offline"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?,
+    );
+    let mut stdin = child.0.stdin.take().ok_or("no stdin")?;
+    let stdout = child.0.stdout.take().ok_or("no stdout")?;
+
+    // Reads Content-Length framed messages
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stdout);
+        loop {
+            let mut length = 0;
+            let mut header = String::new();
+            while reader.read_line(&mut header).is_ok_and(|n| n > 0) {
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.trim().strip_prefix("Content-Length: ") {
+                    length = value.parse().unwrap_or(0);
+                }
+                header.clear();
+            }
+            let mut body = vec![0; length];
+            if length == 0 || reader.read_exact(&mut body).is_err() {
+                return;
+            }
+            if let Ok(message) = serde_json::from_slice::<Value>(&body) {
+                let _ = tx.send(message);
+            }
+        }
+    });
+
+    let mut send = |message: Value| -> std::io::Result<()> {
+        let body = message.to_string();
+        write!(stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+        stdin.flush()
+    };
+    let receive = |matches: &dyn Fn(&Value) -> bool| -> Result<Value, Box<dyn Error>> {
+        loop {
+            let message = rx.recv_timeout(Duration::from_secs(20))?;
+            if matches(&message) {
+                return Ok(message);
+            }
+        }
+    };
+
+    send(
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+    )?;
+    let response = receive(&|m| m["id"] == 1)?;
+    assert_eq!(response["result"]["capabilities"]["hoverProvider"], true);
+    send(json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}))?;
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {
+            "uri": uri,
+            "languageId": "markdown",
+            "version": 1,
+            "text": "# Title\n\n\n\nSee [setup](#setup).\n"
+        }}
+    }))?;
+    let notification = receive(&|m| m["method"] == "textDocument/publishDiagnostics")?;
+    let diagnostics = notification["params"]["diagnostics"]
+        .as_array()
+        .ok_or("no diagnostics")?
+        .clone();
+    assert_eq!(diagnostics.len(), 3, "{:#?}", diagnostics);
+    let md012 = diagnostics
+        .iter()
+        .find(|d| d["code"] == "MD012")
+        .ok_or("no MD012 diagnostic")?;
+    assert_eq!(md012["source"], "md-check");
+    let anchor = diagnostics
+        .iter()
+        .find(|d| d["code"].is_null())
+        .ok_or("no link diagnostic")?;
+    assert!(anchor["message"].as_str().unwrap_or("").contains("#setup"));
+    assert_eq!(
+        anchor["range"]["start"],
+        json!({"line": 4, "character": 12})
+    );
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "textDocument/hover",
+        "params": {"textDocument": {"uri": uri}, "position": md012["range"]["start"]}
+    }))?;
+    let hover = receive(&|m| m["id"] == 2)?;
+    assert!(
+        hover["result"]["contents"]["value"]
+            .as_str()
+            .unwrap_or("")
+            .contains("**MD012**")
+    );
+
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "textDocument/codeAction",
+        "params": {
+            "textDocument": {"uri": uri},
+            "range": md012["range"],
+            "context": {"diagnostics": [md012]}
+        }
+    }))?;
+    let actions = receive(&|m| m["id"] == 3)?;
+    let titles: Vec<&str> = actions["result"]
+        .as_array()
+        .ok_or("no code actions")?
+        .iter()
+        .filter_map(|action| action["title"].as_str())
+        .collect();
+    assert_eq!(
+        titles,
+        vec!["Fix all MD012 problems", "Disable MD012 for this line"]
+    );
+    let fix = &actions["result"][0]["edit"]["changes"][&uri][0];
+    assert_eq!(fix["newText"], "# Title\n\nSee [setup](#setup).\n");
+
+    send(json!({"jsonrpc": "2.0", "id": 4, "method": "shutdown"}))?;
+    receive(&|m| m["id"] == 4)?;
+    send(json!({"jsonrpc": "2.0", "method": "exit"}))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_base_and_staged_report_changed_lines_only() -> Result<(), Box<dyn Error>> {
+    use std::process::Command;
+
+    let temp_dir = TempDir::new()?;
+    let git = |args: &[&str]| -> Result<(), Box<dyn Error>> {
+        let status = Command::new("git")
+            .current_dir(temp_dir.path())
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()?
+            .status;
+        if !status.success() {
+            return Err(format!("git {:?} failed", args).into());
+        }
+        Ok(())
+    };
+
+    // Legacy problems: NO_HTML on line 3, a broken anchor on line 5
+    fs::write(
+        temp_dir.path().join("legacy.md"),
+        "# Legacy\n\n<div>old</div>\n\n[old](#nowhere)\n",
+    )?;
+    fs::write(temp_dir.path().join("clean.md"), "# Clean\n")?;
+    fs::write(temp_dir.path().join("my doc.md"), "# Spaced\n")?;
+    git(&["init", "-q"])?;
+    git(&["add", "."])?;
+    git(&["commit", "-q", "-m", "Initial"])?;
+
+    fs::write(
+        temp_dir.path().join("legacy.md"),
+        "# Legacy\n\n<div>old</div>\n\n[old](#nowhere)\n\n<div>new</div>\n\n[new](#missing)\n",
+    )?;
+    git(&["add", "legacy.md"])?;
+    fs::write(temp_dir.path().join("draft.md"), "# Draft\n\n\n\nText\n")?;
+    fs::write(
+        temp_dir.path().join("my doc.md"),
+        "# Spaced\n\n<div>new</div>\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--diff-base", "HEAD"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Found 3 Markdown files."))
+        .stdout(predicate::str::contains("legacy.md:7:1 Raw HTML"))
+        .stdout(predicate::str::contains("my doc.md:3:1 Raw HTML"))
+        .stdout(predicate::str::contains("#missing"))
+        .stdout(predicate::str::contains("MD012"))
+        .stdout(predicate::str::contains("legacy.md:3:").not())
+        .stdout(predicate::str::contains("#nowhere").not());
+
+    // The untracked draft is not staged, and the staged content is linted
+    // even after the working tree copy changed again
+    fs::write(temp_dir.path().join("legacy.md"), "# Legacy\n")?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--staged"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Found 1 Markdown files."))
+        .stdout(predicate::str::contains("legacy.md:7:1 Raw HTML"))
+        .stdout(predicate::str::contains("MD012").not());
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--diff-base", "no-such-ref"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no-such-ref"));
+
+    Ok(())
+}
+
+#[test]
+fn test_baseline_accepts_recorded_problems() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    let doc = temp_dir.path().join("doc.md");
+    fs::write(&doc, "# Doc\n\n<div>old</div>\n\nSee [old](#nowhere).\n")?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--write-baseline", "baseline.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Recorded 2 problems in baseline.json",
+        ));
+    let baseline = fs::read_to_string(temp_dir.path().join("baseline.json"))?;
+    assert!(baseline.contains(r#""file": "doc.md""#));
+    assert!(baseline.contains(r##""url": "#nowhere""##));
+
+    // Lines shift and a new problem appears
+    fs::write(
+        &doc,
+        "# Doc\n\nIntro.\n\n<div>old</div>\n\n<div>new</div>\n\nSee [old](#nowhere).\n",
+    )?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--baseline", "baseline.json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("doc.md:7:1 Raw HTML"))
+        .stdout(predicate::str::contains(":5:1").not())
+        .stdout(predicate::str::contains("Problematic links").not())
+        .stdout(predicate::str::contains("no longer found").not());
+
+    // Fixed problems are reported as stale
+    fs::write(&doc, "# Doc\n\n<div>old</div>\n")?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args([".", "--baseline", "baseline.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Baseline entries no longer found"))
+        .stdout(predicate::str::contains("doc.md: #nowhere"));
+
+    Ok(())
+}
+
+#[test]
+fn test_lint_and_links_subcommands() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        "# Title\n\n<div>x</div>\n\n[missing](#nowhere)\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["lint"])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("NO_HTML"))
+        .stdout(predicate::str::contains("#nowhere").not());
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["links"])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("#nowhere"))
+        .stdout(predicate::str::contains("NO_HTML").not());
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["check", "--skip-links"])
+        .arg(temp_dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("NO_HTML"));
+
+    Ok(())
+}
+
+#[test]
+fn test_rules_and_explain_subcommands() -> Result<(), Box<dyn Error>> {
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.arg("rules")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("MD012"))
+        .stdout(predicate::str::contains("No Consecutive Empty Lines"))
+        .stdout(predicate::str::contains("enabled"));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["explain", "no_html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("NO_HTML"))
+        .stdout(predicate::str::contains("Bad:"))
+        .stdout(predicate::str::contains("Good:"));
+
+    // markdownlint aliases resolve to md-check's rules
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["explain", "MD013"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("LINE_TOO_LONG"))
+        .stdout(predicate::str::contains("docs/rules.md#line_too_long"));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.args(["explain", "MD999"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown rule 'MD999'"));
+
+    Ok(())
+}
+
+#[test]
+fn test_init_writes_starter_config() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created .md-check.toml"));
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .arg("init")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    // The starter config is valid
+    fs::write(temp_dir.path().join("test.md"), "# Title\n")?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", "."])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_enable_and_disable_rules_by_tag_and_alias() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        format!(
+            "# Title\n\n<div>x</div>\n\n\n{}\n\n<!-- md-check-disable-next-line no-inline-html -->\n<p>y</p>\n",
+            "word ".repeat(30)
+        ),
+    )?;
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[rules]\ndisable = [\"html\", \"whitespace\"]\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", "."])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("LINE_TOO_LONG"))
+        .stdout(predicate::str::contains("NO_HTML").not())
+        .stdout(predicate::str::contains("MD012").not());
+
+    // The command line overrides the config, and aliases name rules
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", ".", "--enable", "html", "--disable", "MD013"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("test.md:3:1 Raw HTML"))
+        .stdout(predicate::str::contains(":9:").not())
+        .stdout(predicate::str::contains("LINE_TOO_LONG").not());
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", ".", "--disable", "typography"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown rule or tag 'typography'"));
+
+    Ok(())
+}
+
+#[test]
+fn test_markdownlint_config_and_comments() -> Result<(), Box<dyn Error>> {
+    let temp_dir = TempDir::new()?;
+    fs::write(
+        temp_dir.path().join("test.md"),
+        format!(
+            "# Title\n\n{}\n\n\n<!-- markdownlint-disable-next-line MD033 -->\n<div>x</div>\n\n<p>y</p>\n",
+            "word ".repeat(10)
+        ),
+    )?;
+    fs::write(
+        temp_dir.path().join(".markdownlint.yaml"),
+        "MD012: false\nline-length:\n  line_length: 40\nMD009: true\n",
+    )?;
+
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", "."])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "test.md:3:41 Line exceeds 40 column limit",
+        ))
+        .stdout(predicate::str::contains("test.md:9:1 Raw HTML"))
+        .stdout(predicate::str::contains(":7:").not())
+        .stdout(predicate::str::contains("MD012").not())
+        .stderr(predicate::str::contains(
+            "MD009 (no-trailing-spaces) is not implemented by md-check; ignoring it",
+        ));
+
+    // md-check's own config takes precedence over markdownlint's
+    fs::write(
+        temp_dir.path().join(".md-check.toml"),
+        "[rules]\nenable = [\"no-multiple-blanks\"]\n\n[rules.line_too_long]\nline_length = 100\n",
+    )?;
+    let mut cmd = cargo_bin_cmd!("md-check");
+    cmd.current_dir(temp_dir.path())
+        .args(["lint", "."])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("test.md:5 Multiple consecutive"))
+        .stdout(predicate::str::contains("LINE_TOO_LONG").not());
+
+    Ok(())
+}